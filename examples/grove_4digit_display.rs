@@ -20,6 +20,10 @@
 //! - D2 (GPIO 2) is used for CLK.
 //! - D1 (GPIO 1) is used for DIO.
 //! - Internal pull-ups are enabled on DIO to assist with signal integrity.
+//!
+//! Numbers, temperatures, clock times and countdowns are formatted into
+//! segment bytes with `xiao_esp32c6_blinky::segments` and written with
+//! `display_slice`.
 
 #![no_std]
 #![no_main]
@@ -35,6 +39,7 @@ use tm1637_embedded_hal::{
     options::{ScrollDirection, ScrollStyle},
     Brightness, TM1637Builder,
 };
+use xiao_esp32c6_blinky::segments::{Formatter, Overflow, Unit};
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
@@ -67,9 +72,10 @@ fn main() -> ! {
     // Initialize the display.
     tm.init().unwrap();
 
+    let format = Formatter::new();
+
     // Display the number `1234`
-    let bytes = [0b00000110, 0b01011011, 0b01001111, 0b01100110];
-    tm.display_slice(0, &bytes).unwrap();
+    tm.display_slice(0, &format.integer(1234)).unwrap();
 
     // We need a delay mechanism since 'delay' was consumed by the builder.
     // esp-hal 1.0 Delay::new() creates a new instance.
@@ -93,9 +99,29 @@ fn main() -> ! {
 
     loop_delay.delay_millis(DELAY_MS);
 
-    // Calculated temperature value.
-    let temperature = 25;
-    tm.options().u8_2(temperature).str(" c").display().unwrap();
+    // Calculated temperature value, in Celsius and Fahrenheit.
+    let temperature = 25.4;
+    tm.display_slice(0, &format.temperature(temperature, Unit::Celsius))
+        .unwrap();
+
+    loop_delay.delay_millis(DELAY_MS);
+
+    tm.display_slice(0, &format.temperature(temperature, Unit::Fahrenheit))
+        .unwrap();
+
+    loop_delay.delay_millis(DELAY_MS);
+
+    // Fixed-point value with two decimals. The Grove display has no decimal
+    // points, so only displays that have them show ` 1.24`.
+    tm.display_slice(0, &format.fixed(1.237, 2)).unwrap();
+
+    loop_delay.delay_millis(DELAY_MS);
+
+    // Values that do not fit: `----`, then `HI`.
+    tm.display_slice(0, &format.integer(12345)).unwrap();
+    loop_delay.delay_millis(DELAY_MS);
+    tm.display_slice(0, &format.with_overflow(Overflow::HiLo).integer(12345))
+        .unwrap();
 
     loop_delay.delay_millis(DELAY_MS);
 
@@ -104,7 +130,7 @@ fn main() -> ! {
 
     // Count from -99 to 99.
     for i in -99..100 {
-        tm.options().r_i16_4(i).display().unwrap();
+        tm.display_slice(0, &format.integer(i)).unwrap();
         loop_delay.delay_millis(10);
     }
 
@@ -116,13 +142,7 @@ fn main() -> ! {
         for second in 0..15 {
             // Shortened second loop for demo speed
             let colon = second % 2 == 0;
-            tm.options()
-                .clock()
-                .hour(23)
-                .minute(minute)
-                .finish()
-                .set_dot(1, colon)
-                .display()
+            tm.display_slice(0, &format.clock(23, minute, colon))
                 .unwrap();
 
             loop_delay.delay_millis(100);
@@ -131,6 +151,15 @@ fn main() -> ! {
 
     loop_delay.delay_millis(DELAY_MS);
 
+    // Countdown from 0:10, blinking the colon every half second
+    for tick in (0..=20).rev() {
+        tm.display_slice(0, &format.countdown(tick / 2, tick % 2 == 0))
+            .unwrap();
+        loop_delay.delay_millis(500);
+    }
+
+    loop_delay.delay_millis(DELAY_MS);
+
     // Scrolling text
     tm.options()
         .str("HELLO ruSt 123 ")
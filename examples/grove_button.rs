@@ -15,7 +15,9 @@
 //! - D1 (Button) => GPIO1
 //! - D7 (LED) => GPIO17
 //!
-//! Behavior: LED turns ON when button is pressed, OFF when released
+//! Behavior: LED turns ON when button is pressed, OFF when released. The
+//! button is debounced, and clicks, double clicks, long presses and
+//! auto-repeat are printed to serial.
 
 #![no_std]
 #![no_main]
@@ -25,7 +27,9 @@ use esp_hal::{
     delay::Delay,
     gpio::{Input, InputConfig, Level, Output, OutputConfig, Pull},
     main,
+    time::Instant,
 };
+use xiao_esp32c6_blinky::button::{Button, Event};
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
@@ -36,24 +40,31 @@ fn main() -> ! {
     // Set GPIO1 (D1) as an input for Grove Button with pull-down resistor
     // Grove buttons are active-high (pressed = HIGH, released = LOW)
     let button_config = InputConfig::default().with_pull(Pull::Down);
-    let button = Input::new(peripherals.GPIO1, button_config);
+    let mut button = Button::new(Input::new(peripherals.GPIO1, button_config))
+        .with_long_press(800)
+        .with_repeat(250);
 
     // Set GPIO17 (D7) as an output for Grove LED, starting LOW (off)
     let mut led = Output::new(peripherals.GPIO17, Level::Low, OutputConfig::default());
 
     let delay = Delay::new();
 
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove Button Example");
+
     loop {
-        // Read button state (is_high() returns true when button is pressed)
-        if button.is_high() {
-            // Button pressed - turn LED on
-            led.set_high();
-        } else {
-            // Button released - turn LED off
-            led.set_low();
+        let now = Instant::now().duration_since_epoch().as_millis() as u32;
+
+        match button.update(now).unwrap() {
+            Some(Event::Pressed) => led.set_high(),
+            Some(Event::Released) => led.set_low(),
+            Some(event) => esp_println::println!("{:?}", event),
+            None => {}
         }
 
-        // Small delay to debounce and reduce CPU usage
-        delay.delay_millis(10);
+        // Poll often enough for the 20ms debounce time
+        delay.delay_millis(5);
     }
 }
@@ -0,0 +1,137 @@
+//! Grove Button Light Sleep Example
+//!
+//! This example handles a Grove Button with GPIO edge interrupts instead of
+//! polling, and puts the chip into light sleep whenever there is nothing to
+//! do, as a battery-powered button device would.
+//! Both modules are connected to the Seeed Studio Grove Base for XIAO
+//! (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Button module connected to D1 connector on Grove Base
+//! - Grove LED module connected to D7 connector on Grove Base
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - D1 (Button) => GPIO1
+//! - D7 (LED) => GPIO17
+//!
+//! Behavior: The GPIO interrupt queues every edge of the button. While the
+//! button is in use the edges are debounced into clicks, double clicks and
+//! long presses (printed to serial, LED follows the button); once it is idle
+//! the chip sleeps until the button level changes again.
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp_backtrace as _;
+use esp_hal::{
+    delay::Delay,
+    gpio::{Input, InputConfig, Io, Level, Output, OutputConfig, Pull},
+    handler, main,
+    rtc_cntl::{sleep::GpioWakeupSource, Rtc},
+    time::Instant,
+};
+use xiao_esp32c6_blinky::{
+    button::{Detector, Event, Timings},
+    edge::{EdgeInputs, EdgeQueue},
+};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+static INPUTS: Mutex<RefCell<Option<EdgeInputs<'static, 1>>>> = Mutex::new(RefCell::new(None));
+static EDGES: EdgeQueue<16> = EdgeQueue::new();
+
+fn now_ms() -> u32 {
+    Instant::now().duration_since_epoch().as_millis() as u32
+}
+
+#[handler]
+fn gpio_handler() {
+    critical_section::with(|cs| {
+        if let Some(inputs) = INPUTS.borrow_ref_mut(cs).as_mut() {
+            inputs.on_interrupt(&EDGES, now_ms());
+        }
+    });
+}
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    let mut io = Io::new(peripherals.IO_MUX);
+    io.set_interrupt_handler(gpio_handler);
+
+    // Grove buttons are active-high (pressed = HIGH, released = LOW)
+    let button = Input::new(
+        peripherals.GPIO1,
+        InputConfig::default().with_pull(Pull::Down),
+    );
+    let inputs = EdgeInputs::new([button]);
+    critical_section::with(|cs| INPUTS.borrow_ref_mut(cs).replace(inputs));
+
+    let mut led = Output::new(peripherals.GPIO17, Level::Low, OutputConfig::default());
+    let mut rtc = Rtc::new(peripherals.LPWR);
+    let delay = Delay::new();
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove Button Light Sleep Example");
+
+    let mut detector = Detector::new(Timings::default());
+    let wakeup = GpioWakeupSource::new();
+
+    loop {
+        // Feed queued edges with their interrupt timestamps
+        while let Some(edge) = EDGES.pop() {
+            if let Some(event) = detector.update(edge.high, edge.timestamp_ms) {
+                handle(event, &mut led);
+            }
+        }
+
+        // Let time pass for debouncing, double clicks and long presses
+        let level = critical_section::with(|cs| {
+            INPUTS
+                .borrow_ref(cs)
+                .as_ref()
+                .is_some_and(|inputs| inputs.level(0))
+        });
+        while let Some(event) = detector.update(level, now_ms()) {
+            handle(event, &mut led);
+        }
+
+        if !detector.is_idle() {
+            delay.delay_millis(5);
+            continue;
+        }
+
+        // Nothing in progress: sleep until the button level changes, unless
+        // an edge arrived in the meantime
+        let armed = critical_section::with(|cs| match INPUTS.borrow_ref_mut(cs).as_mut() {
+            Some(inputs) => inputs.prepare_sleep(&EDGES).unwrap(),
+            None => false,
+        });
+        if !armed {
+            continue;
+        }
+
+        rtc.sleep_light(&[&wakeup]);
+
+        critical_section::with(|cs| {
+            if let Some(inputs) = INPUTS.borrow_ref_mut(cs).as_mut() {
+                inputs.resume(&EDGES, now_ms()).unwrap();
+            }
+        });
+    }
+}
+
+fn handle(event: Event, led: &mut Output) {
+    match event {
+        Event::Pressed => led.set_high(),
+        Event::Released => led.set_low(),
+        event => esp_println::println!("{:?}", event),
+    }
+}
@@ -0,0 +1,93 @@
+//! Grove Multi-Button Example
+//!
+//! This example reads three buttons on D0/D1/D2 with per-input polarity,
+//! latches a Grove LED on and off, and detects chords (buttons held
+//! together). Connected to the Seeed Studio Grove Base for XIAO
+//! (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Button modules connected to D0 and D1 connectors on Grove Base
+//! - A plain (active-low) button between D2 and GND, or an active-low
+//!   Grove-compatible button module on D2
+//! - Grove LED module connected to D7 connector on Grove Base
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - D0 (Button, active-high) => GPIO0
+//! - D1 (Button, active-high) => GPIO1
+//! - D2 (Button, active-low) => GPIO2
+//! - D7 (LED) => GPIO17
+//!
+//! Behavior: D1 toggles the LED on every press (latching). D0 lights the LED
+//! only while held (momentary). Holding D0 and D1 together is a chord that
+//! turns the LED off, and D2 prints its clicks and long presses. All events
+//! are printed to serial.
+
+#![no_std]
+#![no_main]
+
+use esp_backtrace as _;
+use esp_hal::{
+    delay::Delay,
+    gpio::{Input, InputConfig, Level, Output, OutputConfig, Pull},
+    main,
+    time::Instant,
+};
+use xiao_esp32c6_blinky::button::{Button, Buttons, Event, GroupEvent, Polarity, Toggle};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// Button indices in the group
+const MOMENTARY: usize = 0;
+const LATCH: usize = 1;
+
+// D0 + D1 held together
+const CHORD_OFF: u8 = (1 << MOMENTARY) | (1 << LATCH);
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    // Grove buttons are active-high and need a pull-down; buttons to ground
+    // are active-low and need a pull-up
+    let active_high = InputConfig::default().with_pull(Pull::Down);
+    let active_low = InputConfig::default().with_pull(Pull::Up);
+
+    let mut buttons = Buttons::new([
+        Button::new(Input::new(peripherals.GPIO0, active_high)),
+        Button::new(Input::new(peripherals.GPIO1, active_high)),
+        Button::new(Input::new(peripherals.GPIO2, active_low)).with_polarity(Polarity::ActiveLow),
+    ]);
+
+    let mut led = Output::new(peripherals.GPIO17, Level::Low, OutputConfig::default());
+    let mut latch = Toggle::new(Event::Pressed);
+
+    let delay = Delay::new();
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove Multi-Button Example");
+
+    loop {
+        let now = Instant::now().duration_since_epoch().as_millis() as u32;
+
+        if let Some(event) = buttons.update(now).unwrap() {
+            esp_println::println!("{:?}", event);
+
+            match event {
+                GroupEvent::Button(LATCH, event) => {
+                    latch.handle(event);
+                }
+                GroupEvent::Chord(CHORD_OFF) => latch.set(false),
+                _ => {}
+            }
+        }
+
+        // The momentary button lights the LED while held, on top of the latch
+        let momentary = buttons.button(MOMENTARY).is_pressed();
+        led.set_level((latch.is_on() || momentary).into());
+
+        delay.delay_millis(5);
+    }
+}
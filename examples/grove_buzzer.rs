@@ -12,7 +12,9 @@
 //! Pin mapping:
 //! - A2 (Buzzer) => GPIO2
 //!
-//! Behavior: Creates different beep patterns using PWM (analogWrite)
+//! Behavior: Creates different beep patterns using PWM (analogWrite), sends SOS
+//! in Morse code and plays a short melody by changing the PWM frequency for
+//! every note.
 
 #![no_std]
 #![no_main]
@@ -28,27 +30,42 @@ use esp_hal::{
     main,
     time::Rate,
 };
+use xiao_esp32c6_blinky::{
+    buzzer::Buzzer,
+    morse::{self, BuzzerKeyer, Timing},
+    notes::{Note, Pitch},
+};
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
+// Pleasant beep frequency for the buzzer
+const BEEP_HZ: u32 = 2000;
+
+// "Twinkle, Twinkle, Little Star" (first line)
+const MELODY: [Note; 7] = [
+    Note::new(Pitch::C, 5, 4),
+    Note::new(Pitch::C, 5, 4),
+    Note::new(Pitch::G, 5, 4),
+    Note::new(Pitch::G, 5, 4),
+    Note::new(Pitch::A, 5, 4),
+    Note::new(Pitch::A, 5, 4),
+    Note::new(Pitch::G, 5, 2),
+];
+
 #[main]
 fn main() -> ! {
     let peripherals = esp_hal::init(esp_hal::Config::default());
 
-    let delay = Delay::new();
+    let mut delay = Delay::new();
 
     // Initialize LEDC (LED PWM Controller) for PWM output (analogWrite)
     let mut ledc = Ledc::new(peripherals.LEDC);
     ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
 
-    // Configure timer0 with a pleasant frequency for the buzzer
+    // Configure timer0 with the beep frequency; the buzzer changes it per note
     let mut lstimer0 = ledc.timer::<LowSpeed>(timer::Number::Timer0);
     lstimer0
-        .configure(timer::config::Config {
-            duty: timer::config::Duty::Duty8Bit, // 8-bit resolution (0-100%)
-            clock_source: timer::LSClockSource::APBClk,
-            frequency: Rate::from_hz(2000), // 2kHz tone
-        })
+        .configure(Buzzer::timer_config(Rate::from_hz(BEEP_HZ)))
         .unwrap();
 
     // Configure PWM channel on GPIO2 (A2)
@@ -61,6 +78,10 @@ fn main() -> ! {
         })
         .unwrap();
 
+    // The buzzer reconfigures timer0 through its own handle
+    let mut buzzer = Buzzer::new(channel, ledc.timer(timer::Number::Timer0));
+    buzzer.set_tempo(120);
+
     // Give the system time to initialize before printing
     delay.delay_millis(100);
 
@@ -71,55 +92,38 @@ fn main() -> ! {
         // Pattern 1: Three short beeps
         esp_println::println!("\nPattern 1: Three short beeps");
         for _ in 0..3 {
-            channel.set_duty(50).unwrap();
-            delay.delay_millis(200);
-            channel.set_duty(0).unwrap();
+            buzzer.beep(BEEP_HZ, 200, &mut delay).unwrap();
             delay.delay_millis(200);
         }
         delay.delay_millis(1000);
 
         // Pattern 2: Long beep
         esp_println::println!("Pattern 2: Long beep");
-        channel.set_duty(50).unwrap();
-        delay.delay_millis(1000);
-        channel.set_duty(0).unwrap();
+        buzzer.beep(BEEP_HZ, 1000, &mut delay).unwrap();
         delay.delay_millis(1000);
 
         // Pattern 3: SOS pattern (... --- ...)
         esp_println::println!("Pattern 3: SOS pattern");
-        // Three short
-        for _ in 0..3 {
-            channel.set_duty(50).unwrap();
-            delay.delay_millis(200);
-            channel.set_duty(0).unwrap();
-            delay.delay_millis(200);
-        }
-        delay.delay_millis(200);
-        // Three long
-        for _ in 0..3 {
-            channel.set_duty(50).unwrap();
-            delay.delay_millis(600);
-            channel.set_duty(0).unwrap();
-            delay.delay_millis(200);
-        }
-        delay.delay_millis(200);
-        // Three short
-        for _ in 0..3 {
-            channel.set_duty(50).unwrap();
-            delay.delay_millis(200);
-            channel.set_duty(0).unwrap();
-            delay.delay_millis(200);
-        }
+        morse::send(
+            "SOS",
+            Timing::new(6), // 200ms dits
+            &mut BuzzerKeyer::new(&mut buzzer, BEEP_HZ),
+            &mut delay,
+        )
+        .unwrap();
         delay.delay_millis(2000);
 
         // Pattern 4: Rapid beeps
         esp_println::println!("Pattern 4: Rapid beeps");
         for _ in 0..10 {
-            channel.set_duty(50).unwrap();
-            delay.delay_millis(100);
-            channel.set_duty(0).unwrap();
+            buzzer.beep(BEEP_HZ, 100, &mut delay).unwrap();
             delay.delay_millis(100);
         }
         delay.delay_millis(2000);
+
+        // Pattern 5: Melody
+        esp_println::println!("Pattern 5: Melody");
+        buzzer.play(&MELODY, &mut delay).unwrap();
+        delay.delay_millis(2000);
     }
 }
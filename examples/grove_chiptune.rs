@@ -0,0 +1,137 @@
+//! Grove Buzzer Chiptune Example
+//!
+//! This example plays chords on a single Grove Buzzer by arpeggiating them,
+//! the way 8-bit game consoles did, and plays a small tracker pattern both
+//! blocking and in the background with the non-blocking sequencer.
+//! The buzzer is connected to the Seeed Studio Grove Base for XIAO
+//! (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Buzzer module connected to A2 connector on Grove Base
+//! - Signal wire (yellow) is connected to GPIO2 (A2 on XIAO ESP32-C6)
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - A2 (Buzzer) => GPIO2
+//!
+//! Behavior: Plays a C - Am - F - G chord progression, then a startup jingle
+//! written as a 3-channel tracker pattern, first blocking and then from the
+//! sequencer while the main loop keeps counting.
+
+#![no_std]
+#![no_main]
+
+use esp_backtrace as _;
+use esp_hal::{
+    delay::Delay,
+    ledc::{
+        channel::{self, ChannelIFace},
+        timer::{self, TimerIFace},
+        LSGlobalClkSource, Ledc, LowSpeed,
+    },
+    main,
+    time::{Instant, Rate},
+};
+use xiao_esp32c6_blinky::{
+    buzzer::Buzzer,
+    notes::{Chord, Pitch},
+    sequencer::{Sequencer, Step},
+    tracker::Tracker,
+};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+const PROGRESSION: [(Pitch, Chord); 4] = [
+    (Pitch::C, Chord::Major),
+    (Pitch::A, Chord::Minor),
+    (Pitch::F, Chord::Major),
+    (Pitch::G, Chord::Major),
+];
+
+// Startup jingle: melody on channel 1, chord tones on channels 2 and 3
+const JINGLE: &str = "
+    C-5 E-4 G-4
+    E-5 --- ---
+    G-5 --- ---
+    C-6 F-4 A-4
+    --- --- ---
+    B-5 G-4 D-5
+    --- --- ---
+    C-6 E-4 G-4
+    --- --- ---
+    --- --- ---
+    === === ===
+";
+
+// Time per tracker row
+const ROW_MS: u32 = 120;
+
+fn now_ms() -> u32 {
+    Instant::now().duration_since_epoch().as_millis() as u32
+}
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    let mut delay = Delay::new();
+
+    // Initialize LEDC (LED PWM Controller) for the buzzer
+    let mut ledc = Ledc::new(peripherals.LEDC);
+    ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
+
+    let mut lstimer0 = ledc.timer::<LowSpeed>(timer::Number::Timer0);
+    lstimer0
+        .configure(Buzzer::timer_config(Rate::from_hz(440)))
+        .unwrap();
+
+    // Configure PWM channel on GPIO2 (A2)
+    let mut channel = ledc.channel(channel::Number::Channel0, peripherals.GPIO2);
+    channel
+        .configure(channel::config::Config {
+            timer: &lstimer0,
+            duty_pct: 0,
+            drive_mode: esp_hal::gpio::DriveMode::PushPull,
+        })
+        .unwrap();
+
+    let mut buzzer = Buzzer::new(channel, ledc.timer(timer::Number::Timer0));
+    buzzer.set_arpeggio_rate(20);
+
+    // Compile the jingle once so the sequencer can play it in the background
+    let jingle = Tracker::<3>::new(JINGLE, ROW_MS);
+    let mut schedule = [Step::rest(0); 128];
+    let schedule = jingle.compile(&mut schedule).unwrap();
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove Buzzer Chiptune Example");
+    esp_println::println!("Jingle compiles to {} steps", schedule.len());
+
+    let mut sequencer = Sequencer::new();
+
+    loop {
+        esp_println::println!("\nChord progression");
+        for (root, chord) in PROGRESSION {
+            buzzer
+                .play_chord(chord.tones(root, 4), 800, &mut delay)
+                .unwrap();
+        }
+        delay.delay_millis(1000);
+
+        esp_println::println!("Tracker jingle (blocking)");
+        buzzer.play_tracker(&jingle, &mut delay).unwrap();
+        delay.delay_millis(1000);
+
+        esp_println::println!("Tracker jingle (sequencer)");
+        sequencer.play(schedule, false);
+        let mut loops: u32 = 0;
+        while sequencer.poll(&mut buzzer, now_ms()).unwrap() {
+            // The CPU is free for other work while the jingle plays
+            loops += 1;
+        }
+        esp_println::println!("Main loop ran {} times during the jingle", loops);
+        delay.delay_millis(2000);
+    }
+}
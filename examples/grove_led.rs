@@ -24,6 +24,7 @@ use esp_hal::{
     main,
     time::Duration,
 };
+use xiao_esp32c6_blinky as _;
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
@@ -0,0 +1,132 @@
+//! Grove LED Fade Example
+//!
+//! This example dims a Grove LED with LEDC PWM and gamma correction, fading
+//! it in and out with linear and ease-in-out curves, and makes the onboard
+//! LED breathe. The Grove LED is connected to the Seeed Studio Grove Base for
+//! XIAO (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove LED module connected to D7 connector on Grove Base
+//! - Signal wire (yellow) is connected to GPIO17 (D7 on XIAO ESP32-C6)
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - D7 (LED) => GPIO17
+//! - Built-in LED => GPIO15 (active low)
+//!
+//! Behavior: The Grove LED fades in and out linearly, then with ease-in-out,
+//! then breathes for a while, in a loop. The onboard LED breathes slowly the
+//! whole time.
+
+#![no_std]
+#![no_main]
+
+use esp_backtrace as _;
+use esp_hal::{
+    delay::Delay,
+    ledc::{
+        channel::{self, ChannelIFace},
+        timer::{self, TimerIFace},
+        LSGlobalClkSource, Ledc, LowSpeed,
+    },
+    main,
+    time::{Instant, Rate},
+};
+use xiao_esp32c6_blinky::dimmer::{Dimmer, Easing};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+const FADE_MS: u32 = 1500;
+const BREATHE_MS: u32 = 10_000;
+
+fn now_ms() -> u32 {
+    Instant::now().duration_since_epoch().as_millis() as u32
+}
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    // Initialize LEDC (LED PWM Controller)
+    let mut ledc = Ledc::new(peripherals.LEDC);
+    ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
+
+    // 13-bit duty at 1 kHz leaves enough steps for the dimmest levels
+    let mut lstimer0 = ledc.timer::<LowSpeed>(timer::Number::Timer0);
+    lstimer0
+        .configure(timer::config::Config {
+            duty: timer::config::Duty::Duty13Bit,
+            clock_source: timer::LSClockSource::APBClk,
+            frequency: Rate::from_khz(1),
+        })
+        .unwrap();
+
+    // Grove LED on GPIO17 (D7)
+    let mut grove_channel = ledc.channel(channel::Number::Channel0, peripherals.GPIO17);
+    grove_channel
+        .configure(channel::config::Config {
+            timer: &lstimer0,
+            duty_pct: 0,
+            drive_mode: esp_hal::gpio::DriveMode::PushPull,
+        })
+        .unwrap();
+
+    // Onboard LED on GPIO15, which lights when the pin is low
+    let mut onboard_channel = ledc.channel(channel::Number::Channel1, peripherals.GPIO15);
+    onboard_channel
+        .configure(channel::config::Config {
+            timer: &lstimer0,
+            duty_pct: 100,
+            drive_mode: esp_hal::gpio::DriveMode::PushPull,
+        })
+        .unwrap();
+
+    let mut grove_led = Dimmer::new(grove_channel);
+    let mut onboard_led = Dimmer::new(onboard_channel).with_inverted();
+    onboard_led.breathe(0, 255, 4000);
+
+    let delay = Delay::new();
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove LED Fade Example");
+
+    let mut step = 0;
+    let mut breathing_since = None;
+
+    loop {
+        let now = now_ms();
+
+        // Breathing runs until stopped, fades end by themselves
+        let breathing_done =
+            breathing_since.is_some_and(|since: u32| now.wrapping_sub(since) >= BREATHE_MS);
+
+        if !grove_led.is_animating() || breathing_done {
+            breathing_since = None;
+            match step {
+                0 => {
+                    esp_println::println!("Linear fade");
+                    grove_led.fade_to(255, FADE_MS, Easing::Linear);
+                }
+                1 => grove_led.fade_to(0, FADE_MS, Easing::Linear),
+                2 => {
+                    esp_println::println!("Ease-in-out fade");
+                    grove_led.fade_to(255, FADE_MS, Easing::EaseInOut);
+                }
+                3 => grove_led.fade_to(0, FADE_MS, Easing::EaseInOut),
+                _ => {
+                    esp_println::println!("Breathing");
+                    grove_led.breathe(0, 255, 3000);
+                    breathing_since = Some(now);
+                }
+            }
+            step = (step + 1) % 5;
+        }
+
+        grove_led.update(now).unwrap();
+        onboard_led.update(now).unwrap();
+
+        delay.delay_millis(5);
+    }
+}
@@ -0,0 +1,134 @@
+//! Grove Light Alarm Example
+//!
+//! This example sounds an alarm on a Grove Buzzer while a Grove Light Sensor
+//! is covered, using the non-blocking sequencer so the sensor keeps being
+//! read and printed while the alarm plays. Both are connected to the Seeed
+//! Studio Grove Base for XIAO
+//! (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Light Sensor connected to A0 connector on Grove Base
+//! - Grove Buzzer module connected to A2 connector on Grove Base
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - A0 (Light Sensor) => GPIO0 (ADC1_CH0)
+//! - A2 (Buzzer) => GPIO2
+//!
+//! Behavior: A two-tone alarm loops while the light level is below the
+//! threshold and a chime plays when the light comes back. Light values are
+//! printed every 250ms throughout.
+
+#![no_std]
+#![no_main]
+
+use esp_backtrace as _;
+use esp_hal::{
+    delay::Delay,
+    ledc::{
+        channel::{self, ChannelIFace},
+        timer::{self, TimerIFace},
+        LSGlobalClkSource, Ledc, LowSpeed,
+    },
+    main,
+    time::{Instant, Rate},
+};
+use xiao_esp32c6_blinky::{
+    adc::AutoRange,
+    buzzer::Buzzer,
+    sequencer::{Sequencer, ALARM, CHIME},
+};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// Light threshold in millivolts (lower values = darker)
+const LIGHT_THRESHOLD: u16 = 500;
+// Extra light needed to end the alarm, so noise near the threshold is ignored
+const HYSTERESIS: u16 = 50;
+
+// How often the light level is printed
+const REPORT_INTERVAL_MS: u32 = 250;
+
+fn now_ms() -> u32 {
+    Instant::now().duration_since_epoch().as_millis() as u32
+}
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    let delay = Delay::new();
+
+    // Read GPIO0 (A0) on ADC1 with automatic attenuation selection
+    let mut light_sensor = AutoRange::new(peripherals.ADC1, peripherals.GPIO0).with_oversampling(8);
+
+    // Initialize LEDC (LED PWM Controller) for the buzzer
+    let mut ledc = Ledc::new(peripherals.LEDC);
+    ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
+
+    let mut lstimer0 = ledc.timer::<LowSpeed>(timer::Number::Timer0);
+    lstimer0
+        .configure(Buzzer::timer_config(Rate::from_hz(880)))
+        .unwrap();
+
+    // Configure PWM channel on GPIO2 (A2)
+    let mut channel = ledc.channel(channel::Number::Channel0, peripherals.GPIO2);
+    channel
+        .configure(channel::config::Config {
+            timer: &lstimer0,
+            duty_pct: 0,
+            drive_mode: esp_hal::gpio::DriveMode::PushPull,
+        })
+        .unwrap();
+
+    let mut buzzer = Buzzer::new(channel, ledc.timer(timer::Number::Timer0));
+    let mut sequencer = Sequencer::new();
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove Light Alarm Example");
+    esp_println::println!("Cover the light sensor to sound the alarm");
+
+    let mut dark = false;
+    let mut last_report = now_ms();
+
+    loop {
+        let now = now_ms();
+
+        // Advance the alarm; this only touches the buzzer when a step changes
+        if let Err(e) = sequencer.poll(&mut buzzer, now) {
+            esp_println::println!("Buzzer error: {:?}", e);
+        }
+
+        let light_value = match light_sensor.read() {
+            Ok(reading) => reading.millivolts,
+            Err(e) => {
+                esp_println::println!("{}", e);
+                continue;
+            }
+        };
+
+        if !dark && light_value < LIGHT_THRESHOLD {
+            dark = true;
+            esp_println::println!("Dark! Alarm on");
+            sequencer.play(&ALARM, true);
+        } else if dark && light_value >= LIGHT_THRESHOLD + HYSTERESIS {
+            dark = false;
+            esp_println::println!("Light restored");
+            sequencer.play(&CHIME, false);
+        }
+
+        if now.wrapping_sub(last_report) >= REPORT_INTERVAL_MS {
+            last_report = now;
+            let alarm = if sequencer.is_playing() {
+                "playing"
+            } else {
+                "idle"
+            };
+            esp_println::println!("Light: {}mV | Alarm: {}", light_value, alarm);
+        }
+
+        delay.delay_millis(5);
+    }
+}
@@ -18,17 +18,19 @@
 //!
 //! Behavior: The LED turns on when the light level drops below a threshold (dark),
 //! and turns off when there is sufficient light. Light values are printed to serial.
+//! The ADC attenuation is selected automatically, so dark readings use the finer
+//! 0 dB range instead of the coarse 11 dB one.
 
 #![no_std]
 #![no_main]
 
 use esp_backtrace as _;
 use esp_hal::{
-    analog::adc::{Adc, AdcCalLine, AdcConfig, Attenuation},
     delay::Delay,
     gpio::{Level, Output, OutputConfig},
     main,
 };
+use xiao_esp32c6_blinky::adc::AutoRange;
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
@@ -41,18 +43,9 @@ const LIGHT_THRESHOLD: u16 = 500;
 fn main() -> ! {
     let peripherals = esp_hal::init(esp_hal::Config::default());
 
-    // Configure ADC1 for reading the light sensor
-    let mut adc1_config = AdcConfig::new();
-
-    // Enable GPIO0 (A0) as an analog input pin with 11dB attenuation and calibration
-    // (allows reading the full 0-3.3V range with better accuracy)
-    let mut adc_pin = adc1_config.enable_pin_with_cal::<_, AdcCalLine<esp_hal::peripherals::ADC1>>(
-        peripherals.GPIO0,
-        Attenuation::_11dB,
-    );
-
-    // Create ADC instance
-    let mut adc1 = Adc::new(peripherals.ADC1, adc1_config);
+    // Read GPIO0 (A0) on ADC1 with calibration, 8x oversampling and
+    // automatic attenuation selection (0/2.5/6/11 dB)
+    let mut light_sensor = AutoRange::new(peripherals.ADC1, peripherals.GPIO0).with_oversampling(8);
 
     // Set GPIO17 (D7) as an output for Grove LED, starting LOW (off)
     let mut led = Output::new(peripherals.GPIO17, Level::Low, OutputConfig::default());
@@ -73,14 +66,15 @@ fn main() -> ! {
     loop {
         // Read the analog value from light sensor (in millivolts)
         // Higher values = more light, Lower values = darker
-        let light_value: u16 = match nb::block!(adc1.read_oneshot(&mut adc_pin)) {
-            Ok(val) => val,
-            Err(_) => {
-                esp_println::println!("Error reading ADC!");
+        let reading = match light_sensor.read() {
+            Ok(reading) => reading,
+            Err(e) => {
+                esp_println::println!("{}", e);
                 delay.delay_millis(500);
                 continue;
             }
         };
+        let light_value = reading.millivolts;
 
         // Calculate approximate light percentage (0-100%)
         // Adjusted max reference to 2500mV based on observed values (Bright ~1800mV)
@@ -104,18 +98,22 @@ fn main() -> ! {
         if light_value < LIGHT_THRESHOLD {
             led.set_high();
             esp_println::println!(
-                "Light: {} ({}%) - {} | LED: ON",
+                "Light: {} ({}%) - {} | {:?} ({} uV/step) | LED: ON",
                 light_value,
                 light_percent,
-                light_level
+                light_level,
+                reading.attenuation,
+                reading.resolution_uv
             );
         } else {
             led.set_low();
             esp_println::println!(
-                "Light: {} ({}%) - {} | LED: OFF",
+                "Light: {} ({}%) - {} | {:?} ({} uV/step) | LED: OFF",
                 light_value,
                 light_percent,
-                light_level
+                light_level,
+                reading.attenuation,
+                reading.resolution_uv
             );
         }
 
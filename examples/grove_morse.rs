@@ -0,0 +1,115 @@
+//! Grove Morse Code Example
+//!
+//! This example sends text in Morse code on a Grove Buzzer, the built-in LED
+//! and a Grove LED at the same time.
+//! The modules are connected to the Seeed Studio Grove Base for XIAO
+//! (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Buzzer module connected to A2 connector on Grove Base
+//! - Grove LED module connected to D7 connector on Grove Base
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - A2 (Buzzer) => GPIO2
+//! - D7 (LED) => GPIO17
+//! - Built-in LED => GPIO15 (active low)
+//!
+//! Behavior: Sends each message first at 20 WPM, then with Farnsworth
+//! spacing (20 WPM characters at an effective 10 WPM).
+
+#![no_std]
+#![no_main]
+
+use esp_backtrace as _;
+use esp_hal::{
+    delay::Delay,
+    gpio::{Level, Output, OutputConfig},
+    ledc::{
+        channel::{self, ChannelIFace},
+        timer::{self, TimerIFace},
+        LSGlobalClkSource, Ledc, LowSpeed,
+    },
+    main,
+    time::Rate,
+};
+use xiao_esp32c6_blinky::{
+    buzzer::Buzzer,
+    morse::{ActiveLow, BuzzerKeyer, Encoder, Keyer, Timing},
+};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// Sidetone frequency for the buzzer
+const TONE_HZ: u32 = 700;
+
+const MESSAGES: [&str; 2] = ["CQ CQ DE XIAO", "Hello, world!"];
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    let delay = Delay::new();
+
+    // Built-in LED (GPIO15) lights up when driven low
+    let mut onboard_led = ActiveLow(Output::new(
+        peripherals.GPIO15,
+        Level::High,
+        OutputConfig::default(),
+    ));
+    // Grove LED on GPIO17 (D7)
+    let mut grove_led = Output::new(peripherals.GPIO17, Level::Low, OutputConfig::default());
+
+    // Initialize LEDC (LED PWM Controller) for the buzzer
+    let mut ledc = Ledc::new(peripherals.LEDC);
+    ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
+
+    let mut lstimer0 = ledc.timer::<LowSpeed>(timer::Number::Timer0);
+    lstimer0
+        .configure(Buzzer::timer_config(Rate::from_hz(TONE_HZ)))
+        .unwrap();
+
+    // Configure PWM channel on GPIO2 (A2)
+    let mut channel = ledc.channel(channel::Number::Channel0, peripherals.GPIO2);
+    channel
+        .configure(channel::config::Config {
+            timer: &lstimer0,
+            duty_pct: 0,
+            drive_mode: esp_hal::gpio::DriveMode::PushPull,
+        })
+        .unwrap();
+
+    let mut buzzer = Buzzer::new(channel, ledc.timer(timer::Number::Timer0));
+    let mut sidetone = BuzzerKeyer::new(&mut buzzer, TONE_HZ);
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove Morse Code Example");
+
+    let timings = [
+        ("20 WPM", Timing::new(20)),
+        ("Farnsworth 20/10 WPM", Timing::farnsworth(20, 10)),
+    ];
+
+    loop {
+        for (name, timing) in timings {
+            for message in MESSAGES {
+                esp_println::println!("Sending \"{}\" at {}", message, name);
+
+                // Key all outputs together for every event
+                for event in Encoder::new(message, timing) {
+                    onboard_led.key(event.on).unwrap();
+                    grove_led.key(event.on).unwrap();
+                    sidetone.key(event.on).unwrap();
+                    delay.delay_millis(event.duration_ms);
+                }
+
+                onboard_led.key(false).unwrap();
+                grove_led.key(false).unwrap();
+                sidetone.key(false).unwrap();
+                delay.delay_millis(3000);
+            }
+        }
+    }
+}
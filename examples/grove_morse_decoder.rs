@@ -0,0 +1,182 @@
+//! Grove Morse Code Decoder Example
+//!
+//! This example uses a Grove Button as a Morse key and decodes what is keyed
+//! into text, adapting to the sender's speed. Connected to the Seeed Studio
+//! Grove Base for XIAO (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Button module connected to D1 connector on Grove Base
+//! - Grove LED module connected to D7 connector on Grove Base
+//! - Grove OLED Display connected to I2C connector on Grove Base (optional)
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - D1 (Button) => GPIO1
+//! - D7 (LED) => GPIO17
+//! - SDA => GPIO22
+//! - SCL => GPIO23
+//!
+//! Behavior: The LED follows the key. Decoded characters are printed over
+//! serial and, if the OLED is connected, shown on the display together with
+//! the estimated speed. (The 4-Digit Display is not used because its DIO
+//! line shares GPIO1 with the button.)
+
+#![no_std]
+#![no_main]
+
+use embedded_graphics::{
+    mono_font::{ascii::FONT_6X10, MonoTextStyle},
+    pixelcolor::BinaryColor,
+    prelude::*,
+    text::{Baseline, Text},
+};
+use esp_backtrace as _;
+use esp_hal::{
+    delay::Delay,
+    gpio::{Input, InputConfig, Level, Output, OutputConfig, Pull},
+    i2c::master::{Config, I2c},
+    main,
+    time::Instant,
+};
+use ssd1306::{prelude::*, I2CDisplayInterface, Ssd1306};
+use xiao_esp32c6_blinky::morse::{Decoded, Decoder};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// Initial speed guess; the decoder adapts to the sender
+const INITIAL_WPM: u32 = 12;
+
+// Text area of the OLED with the 6x10 font, below the status line
+const COLS: usize = 21;
+const ROWS: usize = 5;
+
+/// Scrolling text shown on the OLED
+struct Screen {
+    lines: [[u8; COLS]; ROWS],
+    column: usize,
+}
+
+impl Screen {
+    fn new() -> Self {
+        Self {
+            lines: [[b' '; COLS]; ROWS],
+            column: 0,
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        if self.column == COLS {
+            self.lines.rotate_left(1);
+            self.lines[ROWS - 1] = [b' '; COLS];
+            self.column = 0;
+        }
+        // Spaces are not needed at the start of a line
+        if c == ' ' && self.column == 0 {
+            return;
+        }
+        self.lines[ROWS - 1][self.column] = if c.is_ascii() { c as u8 } else { b'?' };
+        self.column += 1;
+    }
+}
+
+fn now_ms() -> u32 {
+    Instant::now().duration_since_epoch().as_millis() as u32
+}
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    // Grove buttons are active-high (pressed = HIGH, released = LOW)
+    let button = Input::new(
+        peripherals.GPIO1,
+        InputConfig::default().with_pull(Pull::Down),
+    );
+
+    // The LED lights while the key is down
+    let mut led = Output::new(peripherals.GPIO17, Level::Low, OutputConfig::default());
+
+    let delay = Delay::new();
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove Morse Code Decoder Example");
+
+    // OLED on I2C (GPIO22 SDA, GPIO23 SCL); serial output works without it
+    let i2c = I2c::new(peripherals.I2C0, Config::default())
+        .expect("Failed to create I2C")
+        .with_sda(peripherals.GPIO22)
+        .with_scl(peripherals.GPIO23);
+    let interface = I2CDisplayInterface::new(i2c);
+    let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+        .into_buffered_graphics_mode();
+    let oled = display.init().is_ok();
+    if !oled {
+        esp_println::println!("OLED not found, printing to serial only");
+    }
+
+    let text_style = MonoTextStyle::new(&FONT_6X10, BinaryColor::On);
+
+    let mut decoder = Decoder::new(INITIAL_WPM);
+    let mut screen = Screen::new();
+    let mut redraw = true;
+
+    loop {
+        let pressed = button.is_high();
+        led.set_level(pressed.into());
+
+        let c = match decoder.update(pressed, now_ms()) {
+            Some(Decoded::Char(c)) => Some(c),
+            Some(Decoded::Unknown) => Some('*'),
+            Some(Decoded::WordSpace) => Some(' '),
+            None => None,
+        };
+
+        if let Some(c) = c {
+            esp_println::print!("{}", c);
+            screen.push(c);
+            redraw = true;
+        }
+
+        if oled && redraw {
+            redraw = false;
+            display.clear_buffer();
+
+            // Status line with the estimated speed
+            let wpm = decoder.wpm().min(99) as u8;
+            let status = [b'0' + wpm / 10, b'0' + wpm % 10];
+            Text::with_baseline("Morse", Point::zero(), text_style, Baseline::Top)
+                .draw(&mut display)
+                .unwrap();
+            Text::with_baseline(
+                core::str::from_utf8(&status).unwrap(),
+                Point::new(84, 0),
+                text_style,
+                Baseline::Top,
+            )
+            .draw(&mut display)
+            .unwrap();
+            Text::with_baseline("WPM", Point::new(102, 0), text_style, Baseline::Top)
+                .draw(&mut display)
+                .unwrap();
+
+            for (row, line) in screen.lines.iter().enumerate() {
+                Text::with_baseline(
+                    core::str::from_utf8(line).unwrap(),
+                    Point::new(0, 14 + row as i32 * 10),
+                    text_style,
+                    Baseline::Top,
+                )
+                .draw(&mut display)
+                .unwrap();
+            }
+
+            if let Err(e) = display.flush() {
+                esp_println::println!("Display flush error: {:?}", e);
+            }
+        }
+
+        delay.delay_millis(5);
+    }
+}
@@ -0,0 +1,87 @@
+//! Grove Multi-Channel Analog Example
+//!
+//! This example reads three analog Grove modules at once by scanning the
+//! A0, A1 and A2 connectors round-robin on ADC1. Connected to the
+//! Seeed Studio Grove Base for XIAO
+//! (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Light Sensor connected to A0 connector on Grove Base
+//! - Grove Sound Sensor connected to A1 connector on Grove Base
+//! - Grove Rotary Potentiometer connected to A2 connector on Grove Base
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - A0 (Light Sensor) => GPIO0 (ADC1_CH0)
+//! - A1 (Sound Sensor) => GPIO1 (ADC1_CH1)
+//! - A2 (Potentiometer) => GPIO2 (ADC1_CH2)
+//!
+//! Behavior: All three channels are read every 200ms and printed to serial in mV.
+
+#![no_std]
+#![no_main]
+
+use esp_backtrace as _;
+use esp_hal::{
+    analog::adc::{Adc, AdcCalLine, AdcConfig, Attenuation},
+    delay::Delay,
+    main,
+    peripherals::ADC1,
+};
+use xiao_esp32c6_blinky::adc::AdcScanner;
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// Channel indices in the scanner
+const LIGHT: usize = 0;
+const SOUND: usize = 1;
+const POTENTIOMETER: usize = 2;
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    // Configure ADC1 with all three Grove analog connectors
+    let mut adc1_config = AdcConfig::new();
+
+    // Enable GPIO0-2 (A0-A2) as analog inputs with 11dB attenuation and calibration
+    let mut light_pin = adc1_config
+        .enable_pin_with_cal::<_, AdcCalLine<ADC1>>(peripherals.GPIO0, Attenuation::_11dB);
+    let mut sound_pin = adc1_config
+        .enable_pin_with_cal::<_, AdcCalLine<ADC1>>(peripherals.GPIO1, Attenuation::_11dB);
+    let mut pot_pin = adc1_config
+        .enable_pin_with_cal::<_, AdcCalLine<ADC1>>(peripherals.GPIO2, Attenuation::_11dB);
+
+    // Create ADC instance
+    let adc1 = Adc::new(peripherals.ADC1, adc1_config);
+
+    let mut scanner = AdcScanner::new(adc1, [&mut light_pin, &mut sound_pin, &mut pot_pin]);
+
+    let delay = Delay::new();
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove Multi-Channel Analog Example");
+    esp_println::println!("Scanning A0 (light), A1 (sound) and A2 (potentiometer)...");
+    esp_println::println!();
+
+    loop {
+        match scanner.scan() {
+            Ok(values) => {
+                esp_println::println!(
+                    "Light: {} mV | Sound: {} mV | Potentiometer: {} mV",
+                    values[LIGHT],
+                    values[SOUND],
+                    values[POTENTIOMETER]
+                );
+            }
+            Err(e) => {
+                esp_println::println!("{}", e);
+            }
+        }
+
+        // Wait before next scan
+        delay.delay_millis(200);
+    }
+}
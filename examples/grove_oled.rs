@@ -33,6 +33,7 @@ use esp_hal::{
     main,
 };
 use ssd1306::{prelude::*, I2CDisplayInterface, Ssd1306};
+use xiao_esp32c6_blinky as _;
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
@@ -16,21 +16,35 @@
 //! - A0 (Potentiometer) => GPIO0 (ADC1_CH0)
 //! - D7 (LED) => GPIO17
 //!
-//! Behavior: Rotating the potentiometer changes the LED blink delay from 50ms to 1000ms
+//! Behavior: Rotating the potentiometer changes the LED blink delay from 50ms to 1000ms.
+//! Readings are calibrated to millivolts, oversampled and filtered by a `Knob`
+//! so the delay only changes when the knob is actually turned.
 
 #![no_std]
 #![no_main]
 
 use esp_backtrace as _;
 use esp_hal::{
-    analog::adc::{Adc, AdcConfig, Attenuation},
+    analog::adc::{Adc, AdcCalLine, AdcConfig, Attenuation},
     delay::Delay,
     gpio::{Level, Output, OutputConfig},
     main,
 };
+use xiao_esp32c6_blinky::{adc::read_oversampled, knob::Knob};
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
+// Calibrated readings at the end stops of the potentiometer (in mV)
+const KNOB_MIN_MV: u16 = 20;
+const KNOB_MAX_MV: u16 = 3000;
+
+// Number of ADC conversions averaged per reading
+const OVERSAMPLE: u16 = 16;
+
+// Blink delay range controlled by the knob (in ms)
+const DELAY_MIN_MS: u32 = 50;
+const DELAY_MAX_MS: u32 = 1000;
+
 #[main]
 fn main() -> ! {
     let peripherals = esp_hal::init(esp_hal::Config::default());
@@ -38,9 +52,12 @@ fn main() -> ! {
     // Configure ADC1 for reading the potentiometer
     let mut adc1_config = AdcConfig::new();
 
-    // Enable GPIO0 (A0) as an analog input pin with 11dB attenuation
-    // (allows reading the full 0-3.3V range)
-    let mut adc_pin = adc1_config.enable_pin(peripherals.GPIO0, Attenuation::_11dB);
+    // Enable GPIO0 (A0) as an analog input pin with 11dB attenuation and calibration
+    // (allows reading the full 0-3.3V range in millivolts)
+    let mut adc_pin = adc1_config.enable_pin_with_cal::<_, AdcCalLine<esp_hal::peripherals::ADC1>>(
+        peripherals.GPIO0,
+        Attenuation::_11dB,
+    );
 
     // Create ADC instance
     let mut adc1 = Adc::new(peripherals.ADC1, adc1_config);
@@ -48,6 +65,11 @@ fn main() -> ! {
     // Set GPIO17 (D7) as an output for Grove LED, starting LOW (off)
     let mut led = Output::new(peripherals.GPIO17, Level::Low, OutputConfig::default());
 
+    // Median of 5 readings, light smoothing and a 1% deadband
+    let mut knob = Knob::<5>::new(KNOB_MIN_MV, KNOB_MAX_MV)
+        .with_smoothing(0.3)
+        .with_deadband(0.01);
+
     let delay = Delay::new();
 
     // Give the system time to initialize before printing
@@ -57,22 +79,25 @@ fn main() -> ! {
     esp_println::println!("Rotate the potentiometer to change the LED blink rate");
     esp_println::println!("Starting ADC readings...");
 
+    let mut delay_ms = DELAY_MIN_MS;
+
     loop {
-        // Read the analog value from potentiometer (0-4095 for 12-bit ADC)
-        let adc_value: u16 = match nb::block!(adc1.read_oneshot(&mut adc_pin)) {
+        // Read the averaged analog value from potentiometer (in millivolts)
+        let adc_value: u16 = match read_oversampled(&mut adc1, &mut adc_pin, OVERSAMPLE) {
             Ok(val) => val,
-            Err(_) => {
-                esp_println::println!("Error reading ADC!");
+            Err(e) => {
+                esp_println::println!("{}", e);
                 continue;
             }
         };
 
-        // Map ADC value (0-4095) to delay range (50-1000 ms)
-        // Formula: delay_ms = 50 + (adc_value * 950 / 4095)
-        let delay_ms = 50 + ((adc_value as u32 * 950) / 4095);
+        // Only recompute the delay when the knob actually moved
+        if knob.update(adc_value) {
+            delay_ms = knob.map(DELAY_MIN_MS, DELAY_MAX_MS);
 
-        // Print the ADC value and calculated delay
-        esp_println::println!("ADC Value: {} | Delay: {}ms", adc_value, delay_ms);
+            // Print the ADC value and calculated delay
+            esp_println::println!("ADC Value: {} mV | Delay: {}ms", adc_value, delay_ms);
+        }
 
         // Toggle LED
         led.toggle();
@@ -1,4 +1,4 @@
-//! Grove RGB LED Stick (15-WS2813 Mini) Example using esp-hal-smartled
+//! Grove RGB LED Stick (15-WS2813 Mini) Example
 //!
 //! ⚠️ POWER WARNING ⚠️
 //! The Grove RGB LED Stick with 15 LEDs requires more power than the 3.3V Grove Base
@@ -15,68 +15,210 @@
 //! NC  (White Wire)     ===>    Not Connected
 //!
 //! Note:
-//! - This example uses the `esp-hal-smartled` crate for robust RMT timing.
-//! - The color order is standard GRB for WS2813.
+//! - Frames are sent by the interrupt-driven RMT driver in
+//!   `xiao_esp32c6_blinky::ws2812`, so the next frame is computed while the
+//!   current one is clocked out and the loop never waits for the LEDs.
+//! - The color order is standard GRB for WS2813. Other orders, RGBW LEDs and
+//!   matrices are set up with `xiao_esp32c6_blinky::strip::Strip`.
+//! - Buffers have room for a second chained stick; set `NUM_LEDS` to 30 to
+//!   drive both.
+//! - Frames are gamma corrected, tinted to a warm white and dimmed with
+//!   temporal dithering, then limited to a 500 mA current budget, which a
+//!   USB port can supply.
+//!
+//! Behavior: Cycles through the effects of `xiao_esp32c6_blinky::effects`
+//! (rainbow, theater chase, comet, fire, twinkle, color wipe, breathing and
+//! Larson scanner), showing each for a few seconds.
 
 #![no_std]
 #![no_main]
 
+use core::cell::RefCell;
+
+use critical_section::Mutex;
 use esp_backtrace as _;
-use esp_hal::{delay::Delay, main, rmt::Rmt, time::Rate};
-use esp_hal_smartled::{smart_led_buffer, SmartLedsAdapter};
-use smart_leds::{
-    hsv::{hsv2rgb, Hsv},
-    SmartLedsWrite, RGB8,
+use esp_hal::{
+    handler, main,
+    rmt::Rmt,
+    time::{Instant, Rate},
+};
+use smart_leds::RGB8;
+use xiao_esp32c6_blinky::{
+    color::Correction,
+    effects::Effect,
+    power::PowerLimit,
+    strip::{ColorOrder, Strip},
+    ws2812::{self, buffer_size, StaticBuffer, Ws2812},
 };
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
+// LEDs in use, up to MAX_LEDS
 const NUM_LEDS: usize = 15;
+const MAX_LEDS: usize = 30;
+const BUFFER_SIZE: usize = buffer_size(MAX_LEDS);
+
+static BUFFER: StaticBuffer<BUFFER_SIZE> = StaticBuffer::new();
+static LEDS: Mutex<RefCell<Option<Ws2812<BUFFER_SIZE>>>> = Mutex::new(RefCell::new(None));
+
+const EFFECTS: [(&str, Effect); 8] = [
+    (
+        "Rainbow",
+        Effect::Rainbow {
+            period_ms: 3000,
+            brightness: 255,
+        },
+    ),
+    (
+        "Theater chase",
+        Effect::TheaterChase {
+            color: RGB8::new(255, 160, 0),
+            step_ms: 150,
+        },
+    ),
+    (
+        "Comet",
+        Effect::Comet {
+            color: RGB8::new(0, 128, 255),
+            tail: 6,
+            period_ms: 1500,
+        },
+    ),
+    ("Fire", Effect::Fire { step_ms: 80 }),
+    (
+        "Twinkle",
+        Effect::Twinkle {
+            color: RGB8::new(255, 255, 255),
+            density: 100,
+            period_ms: 1200,
+        },
+    ),
+    (
+        "Color wipe",
+        Effect::ColorWipe {
+            color: RGB8::new(0, 255, 64),
+            step_ms: 100,
+        },
+    ),
+    (
+        "Breathing",
+        Effect::Breathing {
+            color: RGB8::new(160, 0, 255),
+            period_ms: 3000,
+        },
+    ),
+    (
+        "Larson scanner",
+        Effect::Larson {
+            color: RGB8::new(255, 0, 0),
+            width: 3,
+            period_ms: 2000,
+        },
+    ),
+];
+
+// How long each effect is shown
+const EFFECT_MS: u32 = 8000;
+
+// Current the USB port can supply to the stick
+const BUDGET_MA: u32 = 500;
+
+// Global brightness and white point applied to every frame
+const BRIGHTNESS: u8 = 96;
+const TEMPERATURE_K: u32 = 5000;
+
+// 100 fps keeps the dithering of dim levels from flickering
+const FRAME_MS: u32 = 10;
+
+fn now_ms() -> u32 {
+    Instant::now().duration_since_epoch().as_millis() as u32
+}
+
+#[handler]
+fn rmt_handler() {
+    critical_section::with(|cs| {
+        if let Some(leds) = LEDS.borrow_ref_mut(cs).as_mut() {
+            leds.on_interrupt();
+        }
+    });
+}
+
+/// Start sending `strip`, or `WouldBlock` while the last frame is still going
+fn write(strip: &Strip<MAX_LEDS>) -> nb::Result<(), ws2812::Error> {
+    critical_section::with(|cs| match LEDS.borrow_ref_mut(cs).as_mut() {
+        Some(leds) => leds.try_write(strip.wire()),
+        None => Err(nb::Error::WouldBlock),
+    })
+}
 
 #[main]
 fn main() -> ! {
     let peripherals = esp_hal::init(esp_hal::Config::default());
-    let delay = Delay::new();
 
-    esp_println::println!("Grove RGB LED Stick (15-WS2813) SmartLED Example");
+    esp_println::println!("Grove RGB LED Stick (15-WS2813) Example");
 
-    // Initialize RMT
-    let rmt = Rmt::new(peripherals.RMT, Rate::from_mhz(80)).expect("RMT init failed");
+    // Initialize RMT, refilling the channel from its interrupt
+    let mut rmt = Rmt::new(peripherals.RMT, Rate::from_mhz(80)).expect("RMT init failed");
+    rmt.set_interrupt_handler(rmt_handler);
 
-    // Configure RMT buffer
-    let mut rmt_buffer = smart_led_buffer!(NUM_LEDS);
+    let buffer = BUFFER.take().unwrap();
+    let leds = Ws2812::new(rmt.channel0, peripherals.GPIO17, buffer).expect("LED init failed");
+    critical_section::with(|cs| LEDS.borrow_ref_mut(cs).replace(leds));
 
-    // Initialize SmartLedsAdapter
-    let mut led = SmartLedsAdapter::new(rmt.channel0, peripherals.GPIO17, &mut rmt_buffer);
+    // The driver sends bytes in the order the strip arranged them
+    let mut strip = Strip::<MAX_LEDS>::new(NUM_LEDS)
+        .unwrap()
+        .with_order(ColorOrder::Grb);
 
-    esp_println::println!("LED strip initialized!");
+    esp_println::println!("LED strip initialized with {} LEDs!", strip.len());
 
     // Clear
-    let mut data = [RGB8::default(); NUM_LEDS];
-    led.write(data.iter().cloned()).unwrap();
-    delay.delay_millis(100);
+    nb::block!(write(&strip)).unwrap();
 
-    let mut hue: u8 = 0;
+    let mut correction = Correction::<MAX_LEDS>::new().with_temperature(TEMPERATURE_K);
+    correction.set_brightness(BRIGHTNESS);
+    let limit = PowerLimit::new(BUDGET_MA);
+
+    let start = now_ms();
+    let mut current = None;
+    let mut last_frame = start;
+    let mut rendered = false;
+    let mut lost = false;
 
     loop {
-        // Rainbow effect
-        for _ in 0..255 {
-            for (i, led_color) in data.iter_mut().enumerate() {
-                // Hue offset for rainbow
-                let h = hue.wrapping_add((i * 10) as u8);
-                *led_color = hsv2rgb(Hsv {
-                    hue: h,
-                    sat: 255,
-                    val: 100, // Brightness 100/255
-                });
-            }
+        let now = now_ms();
+        let elapsed = now.wrapping_sub(start);
+        let index = (elapsed / EFFECT_MS) as usize % EFFECTS.len();
+        let (name, effect) = EFFECTS[index];
+
+        if current != Some(index) {
+            esp_println::println!("{}", name);
+            current = Some(index);
+        }
 
-            led.write(data.iter().cloned()).unwrap();
+        // Compute the next frame while the current one is clocked out
+        if !rendered {
+            effect.render((elapsed + FRAME_MS) % EFFECT_MS, strip.pixels_mut());
+            correction.apply(strip.pixels_mut());
+            limit.apply(strip.pixels_mut());
+            rendered = true;
+        }
 
-            hue = hue.wrapping_add(1);
-            delay.delay_millis(20);
+        if !lost && now.wrapping_sub(last_frame) >= FRAME_MS {
+            match write(&strip) {
+                Ok(()) => {
+                    last_frame = now;
+                    rendered = false;
+                }
+                Err(nb::Error::WouldBlock) => {}
+                Err(nb::Error::Other(ws2812::Error::ChannelLost)) => {
+                    esp_println::println!("LED channel lost, no more frames");
+                    lost = true;
+                }
+                Err(nb::Error::Other(e)) => esp_println::println!("LED error: {:?}", e),
+            }
         }
 
-        esp_println::println!("Looping rainbow...");
+        // Other work (sensors, buttons...) goes here
     }
 }
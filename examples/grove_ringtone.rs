@@ -0,0 +1,86 @@
+//! Grove Buzzer RTTTL Ringtone Example
+//!
+//! This example plays RTTTL (Nokia ringtone) strings on a Grove Buzzer.
+//! The buzzer is connected to the Seeed Studio Grove Base for XIAO
+//! (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Buzzer module connected to A2 connector on Grove Base
+//! - Signal wire (yellow) is connected to GPIO2 (A2 on XIAO ESP32-C6)
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - A2 (Buzzer) => GPIO2
+//!
+//! Behavior: Plays each ringtone in turn with a pause in between.
+
+#![no_std]
+#![no_main]
+
+use esp_backtrace as _;
+use esp_hal::{
+    delay::Delay,
+    ledc::{
+        channel::{self, ChannelIFace},
+        timer::{self, TimerIFace},
+        LSGlobalClkSource, Ledc, LowSpeed,
+    },
+    main,
+    time::Rate,
+};
+use xiao_esp32c6_blinky::{buzzer::Buzzer, rtttl::Rtttl};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+const RINGTONES: [&str; 3] = [
+    "Nokia:d=4,o=5,b=225:8e6,8d6,f#,g#,8c#6,8b,d,e,8b,8a,c#,e,2a",
+    "FurElise:d=8,o=5,b=125:32p,e6,d#6,e6,d#6,e6,b,d6,c6,4a.,32p,c,e,a,4b.,32p,e,g#,b,4c.6",
+    "OdeToJoy:d=4,o=5,b=140:e,e,f,g,g,f,e,d,c,c,d,e,e.,8d,2d",
+];
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    let mut delay = Delay::new();
+
+    // Initialize LEDC (LED PWM Controller) for the buzzer
+    let mut ledc = Ledc::new(peripherals.LEDC);
+    ledc.set_global_slow_clock(LSGlobalClkSource::APBClk);
+
+    let mut lstimer0 = ledc.timer::<LowSpeed>(timer::Number::Timer0);
+    lstimer0
+        .configure(Buzzer::timer_config(Rate::from_hz(440)))
+        .unwrap();
+
+    // Configure PWM channel on GPIO2 (A2)
+    let mut channel = ledc.channel(channel::Number::Channel0, peripherals.GPIO2);
+    channel
+        .configure(channel::config::Config {
+            timer: &lstimer0,
+            duty_pct: 0,
+            drive_mode: esp_hal::gpio::DriveMode::PushPull,
+        })
+        .unwrap();
+
+    let mut buzzer = Buzzer::new(channel, ledc.timer(timer::Number::Timer0));
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove Buzzer RTTTL Ringtone Example");
+
+    loop {
+        for ringtone in RINGTONES {
+            if let Ok(parsed) = Rtttl::parse(ringtone) {
+                esp_println::println!("Playing: {} ({} bpm)", parsed.name, parsed.bpm());
+            }
+
+            if let Err(e) = buzzer.play_rtttl(ringtone, &mut delay) {
+                esp_println::println!("Failed to play ringtone: {:?}", e);
+            }
+
+            delay.delay_millis(2000);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Grove Rotary Encoder Example
+//!
+//! This example decodes a Grove Encoder with GPIO edge interrupts and a
+//! quadrature state table, with detent counting, acceleration and a push
+//! button. Connected to the Seeed Studio Grove Base for XIAO
+//! (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Encoder connected to the D1 connector on Grove Base, which carries
+//!   its A and B signals on D1 and D2
+//! - Push button (encoder shaft switch or a Grove Button) on D3, active-low
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - D1 (Encoder A) => GPIO1
+//! - D2 (Encoder B) => GPIO2
+//! - D3 (Push button) => GPIO21
+//!
+//! Behavior: Turning prints the signed position and the change; turning
+//! quickly moves up to 5 steps per detent. Clicking the button resets the
+//! position to zero.
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp_backtrace as _;
+use esp_hal::{
+    delay::Delay,
+    gpio::{Input, InputConfig, Io, Pull},
+    handler, main,
+    time::Instant,
+};
+use xiao_esp32c6_blinky::{
+    button::{Button, Event, Polarity},
+    edge::{EdgeInputs, EdgeQueue},
+    encoder::Encoder,
+};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+static INPUTS: Mutex<RefCell<Option<EdgeInputs<'static, 2>>>> = Mutex::new(RefCell::new(None));
+static EDGES: EdgeQueue<64> = EdgeQueue::new();
+
+fn now_ms() -> u32 {
+    Instant::now().duration_since_epoch().as_millis() as u32
+}
+
+#[handler]
+fn gpio_handler() {
+    critical_section::with(|cs| {
+        if let Some(inputs) = INPUTS.borrow_ref_mut(cs).as_mut() {
+            inputs.on_interrupt(&EDGES, now_ms());
+        }
+    });
+}
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    let mut io = Io::new(peripherals.IO_MUX);
+    io.set_interrupt_handler(gpio_handler);
+
+    // Encoder outputs are open contacts to ground
+    let pull_up = InputConfig::default().with_pull(Pull::Up);
+    let inputs = EdgeInputs::new([
+        Input::new(peripherals.GPIO1, pull_up),
+        Input::new(peripherals.GPIO2, pull_up),
+    ]);
+    let mut levels = [inputs.level(0), inputs.level(1)];
+    critical_section::with(|cs| INPUTS.borrow_ref_mut(cs).replace(inputs));
+
+    let mut encoder = Encoder::new(levels[0], levels[1]).with_acceleration(50, 5);
+
+    let mut button =
+        Button::new(Input::new(peripherals.GPIO21, pull_up)).with_polarity(Polarity::ActiveLow);
+
+    let delay = Delay::new();
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove Rotary Encoder Example");
+
+    loop {
+        // Replay the A/B edges recorded by the interrupt handler
+        while let Some(edge) = EDGES.pop() {
+            levels[edge.input] = edge.high;
+            if let Some(delta) = encoder.update(levels[0], levels[1], edge.timestamp_ms) {
+                esp_println::println!("Position: {} ({:+})", encoder.position(), delta);
+            }
+        }
+
+        if let Some(Event::Click) = button.update(now_ms()).unwrap() {
+            encoder.set_position(0);
+            esp_println::println!("Position reset");
+        }
+
+        delay.delay_millis(1);
+    }
+}
@@ -0,0 +1,131 @@
+//! Grove Sound Sensor Fixed-Rate Sampling Example
+//!
+//! This example samples a Grove Sound Sensor at a steady 8 kHz using a
+//! hardware timer interrupt instead of polling the ADC in a delay loop.
+//! Samples are collected into a double buffer and handed to the main loop
+//! in blocks of 256, which prints per-block statistics.
+//! Connected to the Seeed Studio Grove Base for XIAO
+//! (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Sound Sensor connected to A0 connector on Grove Base
+//! - Sound sensor signal wire (yellow) is connected to GPIO0 (A0 on XIAO ESP32-C6)
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - A0 (Sound Sensor) => GPIO0 (ADC1_CH0)
+//!
+//! Behavior: Prints min/max/mean and peak-to-peak level (in mV) for every
+//! block of 256 samples (32 ms of audio at 8 kHz).
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp_backtrace as _;
+use esp_hal::{
+    analog::adc::{Adc, AdcCalLine, AdcConfig, Attenuation},
+    handler, main,
+    peripherals::{ADC1, GPIO0},
+    time::Rate,
+    timer::{timg::TimerGroup, PeriodicTimer},
+};
+use xiao_esp32c6_blinky::sampler::AdcSampler;
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+// Sample rate for the sound sensor
+const SAMPLE_RATE_HZ: u32 = 8_000;
+
+// Number of samples per block handed to the application
+const BLOCK_SIZE: usize = 256;
+
+type SoundSampler =
+    AdcSampler<'static, ADC1<'static>, GPIO0<'static>, AdcCalLine<ADC1<'static>>, BLOCK_SIZE>;
+
+// Shared between the timer interrupt and the main loop
+static SAMPLER: Mutex<RefCell<Option<SoundSampler>>> = Mutex::new(RefCell::new(None));
+
+#[handler]
+fn sample_tick() {
+    critical_section::with(|cs| {
+        if let Some(sampler) = SAMPLER.borrow_ref_mut(cs).as_mut() {
+            sampler.on_interrupt();
+        }
+    });
+}
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    // Configure ADC1 for reading the sound sensor
+    let mut adc1_config = AdcConfig::new();
+
+    // Enable GPIO0 (A0) as an analog input pin with 11dB attenuation and calibration
+    let adc_pin = adc1_config
+        .enable_pin_with_cal::<_, AdcCalLine<ADC1>>(peripherals.GPIO0, Attenuation::_11dB);
+
+    // Create ADC instance
+    let adc1 = Adc::new(peripherals.ADC1, adc1_config);
+
+    // Timer 0 of timer group 0 paces the conversions
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    let mut timer = PeriodicTimer::new(timg0.timer0);
+    timer.set_interrupt_handler(sample_tick);
+
+    let sampler = AdcSampler::new(adc1, adc_pin, timer, Rate::from_hz(SAMPLE_RATE_HZ));
+
+    // Start the stored sampler, so the first tick already finds it
+    critical_section::with(|cs| {
+        SAMPLER
+            .borrow_ref_mut(cs)
+            .insert(sampler)
+            .start()
+            .expect("Failed to start sample timer");
+    });
+
+    esp_println::println!("Grove Sound Sensor Sampling Example");
+    esp_println::println!(
+        "Sampling at {} Hz in blocks of {} samples",
+        SAMPLE_RATE_HZ,
+        BLOCK_SIZE
+    );
+
+    let mut block = [0u16; BLOCK_SIZE];
+    let mut block_count: u32 = 0;
+
+    loop {
+        // Copy the finished block out while holding the lock as briefly as possible
+        let (ready, overruns) = critical_section::with(|cs| {
+            let mut sampler = SAMPLER.borrow_ref_mut(cs);
+            let sampler = sampler.as_mut().unwrap();
+            (sampler.take(&mut block), sampler.overruns())
+        });
+
+        if !ready {
+            continue;
+        }
+
+        let min = block.iter().copied().min().unwrap_or(0);
+        let max = block.iter().copied().max().unwrap_or(0);
+        let mean = block.iter().map(|&s| s as u32).sum::<u32>() / BLOCK_SIZE as u32;
+
+        block_count = block_count.wrapping_add(1);
+
+        // Print every 16th block (~0.5 s) to keep the console readable
+        if block_count.is_multiple_of(16) {
+            esp_println::println!(
+                "Block {}: min {} mV | max {} mV | mean {} mV | p-p {} mV | overruns {}",
+                block_count,
+                min,
+                max,
+                mean,
+                max - min,
+                overruns
+            );
+        }
+    }
+}
@@ -29,6 +29,7 @@ use esp_hal::{
     gpio::{Level, Output, OutputConfig},
     main,
 };
+use xiao_esp32c6_blinky as _;
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
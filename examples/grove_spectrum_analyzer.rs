@@ -0,0 +1,201 @@
+//! Grove Sound Sensor Spectrum Analyzer Example
+//!
+//! This example samples a Grove Sound Sensor at 8 kHz, runs a 256-point FFT
+//! over every block and shows the frequency content as a bar graph on the
+//! Grove OLED Display and as colored bands on the Grove RGB LED Stick.
+//! Connected to the Seeed Studio Grove Base for XIAO
+//! (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! ⚠️ POWER WARNING ⚠️
+//! The RGB LED Stick must be powered from the 5V pin on the XIAO headers.
+//!
+//! The following wiring is assumed:
+//! - Grove Sound Sensor connected to A0 connector on Grove Base
+//! - Grove OLED Display connected to I2C connector on Grove Base
+//! - Grove RGB LED Stick DIN (yellow) connected to D7 (GPIO17), VCC to 5V pin
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - A0 (Sound Sensor) => GPIO0 (ADC1_CH0)
+//! - SDA => GPIO22
+//! - SCL => GPIO23
+//! - D7 (RGB Stick) => GPIO17
+//!
+//! Note:
+//! - The 8 kHz sample interrupt would stall a polled RMT transmission, so
+//!   the stick is driven by the interrupt-refilled driver in
+//!   `xiao_esp32c6_blinky::ws2812`. Its two blocks of channel RAM leave the
+//!   refill about 60 us, far longer than one ADC conversion.
+//!
+//! Behavior: 32 logarithmic bands from ~31 Hz to 4 kHz are drawn on the OLED,
+//! and the 15 LEDs of the stick light up from red (bass) to violet (treble).
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use embedded_graphics::{prelude::*, primitives::Rectangle};
+use esp_backtrace as _;
+use esp_hal::{
+    analog::adc::{Adc, AdcCalLine, AdcConfig, Attenuation},
+    handler,
+    i2c::master::{Config, I2c},
+    main,
+    peripherals::{ADC1, GPIO0},
+    rmt::Rmt,
+    time::Rate,
+    timer::{timg::TimerGroup, PeriodicTimer},
+};
+use smart_leds::RGB8;
+use ssd1306::{prelude::*, I2CDisplayInterface, Ssd1306};
+use xiao_esp32c6_blinky::{
+    fft::{Fft, Window},
+    sampler::AdcSampler,
+    spectrum::Spectrum,
+    strip::ColorOrder,
+    ws2812::{self, buffer_size, StaticBuffer, Ws2812},
+};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+const SAMPLE_RATE_HZ: u32 = 8_000;
+const FFT_SIZE: usize = 256;
+const BANDS: usize = 32;
+const NUM_LEDS: usize = 15;
+
+// Levels shown on the display: 0 dB (1 mV) to 50 dB (~316 mV)
+const FLOOR_DB: f32 = 0.0;
+const RANGE_DB: f32 = 50.0;
+
+type SoundSampler =
+    AdcSampler<'static, ADC1<'static>, GPIO0<'static>, AdcCalLine<ADC1<'static>>, FFT_SIZE>;
+
+static SAMPLER: Mutex<RefCell<Option<SoundSampler>>> = Mutex::new(RefCell::new(None));
+
+const BUFFER_SIZE: usize = buffer_size(NUM_LEDS);
+
+static BUFFER: StaticBuffer<BUFFER_SIZE> = StaticBuffer::new();
+static LEDS: Mutex<RefCell<Option<Ws2812<BUFFER_SIZE>>>> = Mutex::new(RefCell::new(None));
+
+#[handler]
+fn sample_tick() {
+    critical_section::with(|cs| {
+        if let Some(sampler) = SAMPLER.borrow_ref_mut(cs).as_mut() {
+            sampler.on_interrupt();
+        }
+    });
+}
+
+#[handler]
+fn rmt_handler() {
+    critical_section::with(|cs| {
+        if let Some(leds) = LEDS.borrow_ref_mut(cs).as_mut() {
+            leds.on_interrupt();
+        }
+    });
+}
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    esp_println::println!("Grove Sound Sensor Spectrum Analyzer Example");
+
+    // OLED on I2C (GPIO22 SDA, GPIO23 SCL)
+    let i2c = I2c::new(peripherals.I2C0, Config::default())
+        .expect("Failed to create I2C")
+        .with_sda(peripherals.GPIO22)
+        .with_scl(peripherals.GPIO23);
+    let interface = I2CDisplayInterface::new(i2c);
+    let mut display = Ssd1306::new(interface, DisplaySize128x64, DisplayRotation::Rotate0)
+        .into_buffered_graphics_mode();
+    display.init().expect("Failed to initialize display");
+
+    // RGB stick on GPIO17, refilled from the RMT interrupt
+    let mut rmt = Rmt::new(peripherals.RMT, Rate::from_mhz(80)).expect("RMT init failed");
+    rmt.set_interrupt_handler(rmt_handler);
+    let buffer = BUFFER.take().unwrap();
+    let leds = Ws2812::new(rmt.channel0, peripherals.GPIO17, buffer).expect("LED init failed");
+    critical_section::with(|cs| LEDS.borrow_ref_mut(cs).replace(leds));
+
+    // Sound sensor on GPIO0, sampled from TIMG0 timer 0
+    let mut adc1_config = AdcConfig::new();
+    let adc_pin = adc1_config
+        .enable_pin_with_cal::<_, AdcCalLine<ADC1>>(peripherals.GPIO0, Attenuation::_11dB);
+    let adc1 = Adc::new(peripherals.ADC1, adc1_config);
+
+    let timg0 = TimerGroup::new(peripherals.TIMG0);
+    let mut timer = PeriodicTimer::new(timg0.timer0);
+    timer.set_interrupt_handler(sample_tick);
+
+    let sampler = AdcSampler::new(adc1, adc_pin, timer, Rate::from_hz(SAMPLE_RATE_HZ));
+
+    // Start the stored sampler, so the first tick already finds it
+    critical_section::with(|cs| {
+        SAMPLER
+            .borrow_ref_mut(cs)
+            .insert(sampler)
+            .start()
+            .expect("Failed to start sample timer");
+    });
+
+    let fft = Fft::<FFT_SIZE>::new(Window::Hann);
+    let mut spectrum = Spectrum::<BANDS>::new(FLOOR_DB, RANGE_DB);
+
+    esp_println::println!(
+        "FFT: {} points, {} Hz per bin",
+        FFT_SIZE,
+        Fft::<FFT_SIZE>::bin_frequency(1, SAMPLE_RATE_HZ)
+    );
+
+    let mut block = [0u16; FFT_SIZE];
+    let mut bins = [0.0f32; FFT_SIZE / 2];
+    let mut colors = [RGB8::default(); NUM_LEDS];
+
+    loop {
+        let ready = critical_section::with(|cs| {
+            SAMPLER
+                .borrow_ref_mut(cs)
+                .as_mut()
+                .is_some_and(|sampler| sampler.take(&mut block))
+        });
+
+        if !ready {
+            continue;
+        }
+
+        fft.magnitudes(&block, &mut bins);
+        spectrum.update(&bins);
+
+        display.clear_buffer();
+        spectrum
+            .draw(
+                &mut display,
+                Rectangle::new(Point::zero(), Size::new(128, 64)),
+            )
+            .unwrap();
+        if let Err(e) = display.flush() {
+            esp_println::println!("Display flush error: {:?}", e);
+        }
+
+        // A frame takes under half a millisecond, so the previous one has
+        // finished long before the next block; a busy driver skips a frame
+        spectrum.render_leds(&mut colors, 64);
+        let wire = colors.iter().map(|&color| ColorOrder::Grb.arrange(color));
+        let written = critical_section::with(|cs| match LEDS.borrow_ref_mut(cs).as_mut() {
+            Some(leds) => leds.try_write(wire),
+            None => Ok(()),
+        });
+        match written {
+            Ok(()) | Err(nb::Error::WouldBlock) => {}
+            Err(nb::Error::Other(ws2812::Error::ChannelLost)) => {
+                // Drop the driver so later frames only update the display
+                esp_println::println!("LED channel lost, display only");
+                critical_section::with(|cs| LEDS.borrow_ref_mut(cs).take());
+            }
+            Err(nb::Error::Other(e)) => esp_println::println!("LED error: {:?}", e),
+        }
+    }
+}
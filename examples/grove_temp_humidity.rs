@@ -25,6 +25,7 @@ use esp_hal::{
     i2c::master::{Config, I2c},
     main,
 };
+use xiao_esp32c6_blinky as _;
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
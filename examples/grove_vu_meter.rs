@@ -0,0 +1,109 @@
+//! Grove VU Meter Example
+//!
+//! This example shows the level of a Grove Sound Sensor as a bar graph on
+//! the Grove RGB LED Stick, with a green-yellow-red gradient, slow decay and
+//! a peak-hold dot. The sound sensor is connected to the Seeed Studio Grove
+//! Base for XIAO (https://www.seeedstudio.com/Grove-Shield-for-Seeeduino-XIAO-p-4621.html)
+//!
+//! The following wiring is assumed:
+//! - Grove Sound Sensor connected to A0 connector on Grove Base
+//! - Grove RGB LED Stick powered from the 5V pin, DIN (yellow) on D7
+//!   (see `grove_rgb_stick.rs` for the power warning)
+//!   https://github.com/espressif/arduino-esp32/blob/master/variants/XIAO_ESP32C6/pins_arduino.h
+//!
+//! Pin mapping:
+//! - A0 (Sound Sensor) => GPIO0 (ADC1_CH0)
+//! - D7 (RGB LED Stick DIN) => GPIO17
+//!
+//! Behavior: Every 20 ms the loudest deviation from the silence baseline is
+//! shown on the stick. The bar jumps up with the sound, falls back slowly and
+//! leaves a white dot at the recent peak for a second.
+
+#![no_std]
+#![no_main]
+
+use esp_backtrace as _;
+use esp_hal::{
+    analog::adc::{Adc, AdcCalLine, AdcConfig, Attenuation},
+    delay::Delay,
+    main,
+    rmt::Rmt,
+    time::{Instant, Rate},
+};
+use esp_hal_smartled::{smart_led_buffer, SmartLedsAdapter};
+use smart_leds::{SmartLedsWrite, RGB8};
+use xiao_esp32c6_blinky::{meter::BarGraph, power::PowerLimit};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+const NUM_LEDS: usize = 15;
+
+// Deviation from the baseline that fills the bar (in mV)
+const FULL_SCALE_MV: f32 = 1200.0;
+
+// Length of one measurement window, one frame each
+const WINDOW_MS: u32 = 20;
+
+// Current the USB port can supply to the stick
+const BUDGET_MA: u32 = 500;
+
+fn now_ms() -> u32 {
+    Instant::now().duration_since_epoch().as_millis() as u32
+}
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default());
+
+    // Sound sensor on GPIO0 (A0) with 11dB attenuation and calibration
+    let mut adc1_config = AdcConfig::new();
+    let mut adc_pin = adc1_config.enable_pin_with_cal::<_, AdcCalLine<esp_hal::peripherals::ADC1>>(
+        peripherals.GPIO0,
+        Attenuation::_11dB,
+    );
+    let mut adc1 = Adc::new(peripherals.ADC1, adc1_config);
+
+    // RGB LED Stick on GPIO17 (D7)
+    let rmt = Rmt::new(peripherals.RMT, Rate::from_mhz(80)).expect("RMT init failed");
+    let mut rmt_buffer = smart_led_buffer!(NUM_LEDS);
+    let mut leds = SmartLedsAdapter::new(rmt.channel0, peripherals.GPIO17, &mut rmt_buffer);
+
+    let delay = Delay::new();
+
+    // Give the system time to initialize before printing
+    delay.delay_millis(100);
+
+    esp_println::println!("Grove VU Meter Example");
+    esp_println::println!("Calibrating baseline (silence) level...");
+
+    let mut baseline_acc: u32 = 0;
+    let samples = 50;
+    for _ in 0..samples {
+        let val: u16 = nb::block!(adc1.read_oneshot(&mut adc_pin)).unwrap_or(0);
+        baseline_acc += val as u32;
+        delay.delay_millis(10);
+    }
+    let baseline = (baseline_acc / samples) as u16;
+
+    esp_println::println!("Baseline calibrated: {} mV", baseline);
+
+    let mut meter = BarGraph::new(0.0, FULL_SCALE_MV).with_peak_hold(1000, 0.5);
+    let limit = PowerLimit::new(BUDGET_MA);
+    let mut frame = [RGB8::default(); NUM_LEDS];
+
+    loop {
+        // Loudest deviation within the window
+        let start = now_ms();
+        let mut loudest = 0;
+        while now_ms().wrapping_sub(start) < WINDOW_MS {
+            if let Ok(val) = nb::block!(adc1.read_oneshot(&mut adc_pin)) {
+                loudest = loudest.max(val.abs_diff(baseline));
+            }
+        }
+
+        meter.update(loudest as f32, now_ms());
+        meter.render(&mut frame);
+        limit.apply(&mut frame);
+        leds.write(frame.iter().cloned()).unwrap();
+    }
+}
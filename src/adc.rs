@@ -0,0 +1,259 @@
+//! ADC helpers shared by the analog examples
+//!
+//! [`read_oversampled`] averages several conversions of an already
+//! configured pin. [`AutoRange`] additionally picks the attenuation
+//! (0/2.5/6/11 dB) that best fits the signal, so low voltages such as a
+//! light sensor in the dark are measured with a finer step than the
+//! usual fixed 11 dB setting allows. [`AdcScanner`] reads several pins
+//! (e.g. the A0/A1/A2 Grove connectors) round-robin on a single ADC.
+
+use esp_hal::{
+    analog::adc::{
+        Adc, AdcCalLine, AdcCalScheme, AdcChannel, AdcConfig, AdcPin, Attenuation, RegisterAccess,
+    },
+    gpio::AnalogPin,
+    peripherals::ADC1,
+    Blocking,
+};
+
+/// Read `samples` conversions back to back and return their average.
+///
+/// Averaging N readings reduces white noise by roughly sqrt(N). With a
+/// calibration scheme on the pin the result is in millivolts, otherwise in
+/// raw counts.
+pub fn read_oversampled<'d, ADCI, PIN, CS>(
+    adc: &mut Adc<'d, ADCI, Blocking>,
+    pin: &mut AdcPin<PIN, ADCI, CS>,
+    samples: u16,
+) -> Result<u16, &'static str>
+where
+    ADCI: RegisterAccess + 'd,
+    PIN: AdcChannel,
+    CS: AdcCalScheme<ADCI>,
+{
+    let samples = samples.max(1);
+    let mut sum: u32 = 0;
+    for _ in 0..samples {
+        sum += nb::block!(adc.read_oneshot(pin)).map_err(|_| "Failed to read ADC")? as u32;
+    }
+    Ok((sum / samples as u32) as u16)
+}
+
+/// Attenuations from lowest to highest input range
+const ATTENUATIONS: [Attenuation; 4] = [
+    Attenuation::_0dB,
+    Attenuation::_2p5dB,
+    Attenuation::_6dB,
+    Attenuation::_11dB,
+];
+
+/// Recommended input range of an attenuation on the ESP32-C6, in millivolts.
+///
+/// Readings above this value lose accuracy even though the ADC may not be
+/// saturated yet. See "ADC Characteristics" in the ESP32-C6 datasheet.
+pub fn range_mv(attenuation: Attenuation) -> u16 {
+    match attenuation {
+        Attenuation::_0dB => 750,
+        Attenuation::_2p5dB => 1050,
+        Attenuation::_6dB => 1300,
+        Attenuation::_11dB => 2500,
+    }
+}
+
+/// A calibrated reading together with how precise it is
+#[derive(Clone, Copy, Debug)]
+pub struct Reading {
+    /// Calibrated input voltage
+    pub millivolts: u16,
+    /// Attenuation the reading was taken with
+    pub attenuation: Attenuation,
+    /// Effective number of bits after oversampling
+    pub effective_bits: f32,
+    /// Smallest resolvable voltage step, in microvolts
+    pub resolution_uv: u32,
+}
+
+/// Oversampling ADC reader that picks the attenuation to fit the signal.
+///
+/// Starting from the current attenuation, a reading close to the top of the
+/// range switches to the next higher attenuation and a reading that would
+/// comfortably fit into a lower range switches down, so small signals are
+/// measured with the finest step available. Only the ESP32-C6's ADC1 exists,
+/// so the reader is tied to it.
+///
+/// The ADC driver and the calibrated pin are recreated for every read,
+/// which costs a few microseconds but allows switching attenuation freely.
+pub struct AutoRange<'d, PIN> {
+    adc: ADC1<'d>,
+    pin: Option<PIN>,
+    attenuation: Attenuation,
+    oversample: u16,
+}
+
+impl<'d, PIN> AutoRange<'d, PIN>
+where
+    PIN: AdcChannel + AnalogPin,
+{
+    // Switch up above this fraction of the current range
+    const UPPER: u32 = 90;
+    // Switch down below this fraction of the next lower range
+    const LOWER: u32 = 80;
+
+    /// Create a reader starting at 11 dB (safe for any input up to 3.3 V)
+    pub fn new(adc: ADC1<'d>, pin: PIN) -> Self {
+        Self {
+            adc,
+            pin: Some(pin),
+            attenuation: Attenuation::_11dB,
+            oversample: 1,
+        }
+    }
+
+    /// Average `samples` conversions for every reading
+    pub fn with_oversampling(mut self, samples: u16) -> Self {
+        self.oversample = samples.max(1);
+        self
+    }
+
+    /// Attenuation used for the last reading
+    pub fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    /// Take a calibrated reading, adjusting the attenuation if needed
+    pub fn read(&mut self) -> Result<Reading, &'static str> {
+        let mut millivolts = self.read_with(self.attenuation)?;
+
+        // Step through the ranges until the reading fits the attenuation
+        for _ in 0..ATTENUATIONS.len() {
+            let Some(next) = self.next_attenuation(millivolts) else {
+                break;
+            };
+            self.attenuation = next;
+            millivolts = self.read_with(next)?;
+        }
+
+        Ok(self.reading(millivolts))
+    }
+
+    /// Take a calibrated reading with a fixed attenuation
+    pub fn read_with(&mut self, attenuation: Attenuation) -> Result<u16, &'static str> {
+        let pin = self.pin.take().ok_or("ADC pin unavailable")?;
+
+        let mut config = AdcConfig::new();
+        let mut adc_pin = config.enable_pin_with_cal::<_, AdcCalLine<ADC1<'_>>>(pin, attenuation);
+        let mut adc = Adc::new(self.adc.reborrow(), config);
+
+        let result = read_oversampled(&mut adc, &mut adc_pin, self.oversample);
+        self.pin = Some(adc_pin.pin);
+        result
+    }
+
+    fn next_attenuation(&self, millivolts: u16) -> Option<Attenuation> {
+        let index = ATTENUATIONS.iter().position(|&a| a == self.attenuation)?;
+        let millivolts = millivolts as u32;
+
+        if index + 1 < ATTENUATIONS.len()
+            && millivolts * 100 > range_mv(self.attenuation) as u32 * Self::UPPER
+        {
+            return Some(ATTENUATIONS[index + 1]);
+        }
+
+        if index > 0 && millivolts * 100 < range_mv(ATTENUATIONS[index - 1]) as u32 * Self::LOWER {
+            return Some(ATTENUATIONS[index - 1]);
+        }
+
+        None
+    }
+
+    fn reading(&self, millivolts: u16) -> Reading {
+        // Oversampling by 4x gains one bit over the 12-bit converter
+        let effective_bits = 12.0 + 0.5 * libm::log2f(self.oversample as f32);
+        let resolution_uv =
+            (range_mv(self.attenuation) as f32 * 1000.0 / libm::exp2f(effective_bits)) as u32;
+
+        Reading {
+            millivolts,
+            attenuation: self.attenuation,
+            effective_bits,
+            resolution_uv,
+        }
+    }
+}
+
+/// A configured ADC input that can be read by an [`AdcScanner`].
+///
+/// Implemented for every [`AdcPin`], so pins of different GPIO types and
+/// calibration schemes can share one scanner.
+pub trait ScanChannel<'d, ADCI> {
+    /// Start or continue a conversion on this channel
+    fn read(&mut self, adc: &mut Adc<'d, ADCI, Blocking>) -> nb::Result<u16, ()>;
+}
+
+impl<'d, ADCI, PIN, CS> ScanChannel<'d, ADCI> for AdcPin<PIN, ADCI, CS>
+where
+    ADCI: RegisterAccess + 'd,
+    PIN: AdcChannel,
+    CS: AdcCalScheme<ADCI>,
+{
+    fn read(&mut self, adc: &mut Adc<'d, ADCI, Blocking>) -> nb::Result<u16, ()> {
+        adc.read_oneshot(self)
+    }
+}
+
+/// Round-robin reader for several analog inputs on one ADC.
+///
+/// The latest value of every channel is kept in an array indexed in the
+/// order the channels were passed to [`AdcScanner::new`].
+pub struct AdcScanner<'d, 'p, ADCI, const C: usize> {
+    adc: Adc<'d, ADCI, Blocking>,
+    channels: [&'p mut dyn ScanChannel<'d, ADCI>; C],
+    values: [u16; C],
+    next: usize,
+}
+
+impl<'d, 'p, ADCI, const C: usize> AdcScanner<'d, 'p, ADCI, C>
+where
+    ADCI: RegisterAccess + 'd,
+{
+    /// Create a scanner over `channels`, all enabled on the config of `adc`
+    pub fn new(
+        adc: Adc<'d, ADCI, Blocking>,
+        channels: [&'p mut dyn ScanChannel<'d, ADCI>; C],
+    ) -> Self {
+        Self {
+            adc,
+            channels,
+            values: [0; C],
+            next: 0,
+        }
+    }
+
+    /// Read every channel once and return the updated values
+    pub fn scan(&mut self) -> Result<&[u16; C], &'static str> {
+        for _ in 0..C {
+            self.step()?;
+        }
+        Ok(&self.values)
+    }
+
+    /// Read the next channel in turn. Returns the index that was updated.
+    pub fn step(&mut self) -> Result<usize, &'static str> {
+        let index = self.next;
+        let value = nb::block!(self.channels[index].read(&mut self.adc))
+            .map_err(|_| "Failed to read ADC")?;
+        self.values[index] = value;
+        self.next = (index + 1) % C;
+        Ok(index)
+    }
+
+    /// Latest value of every channel
+    pub fn values(&self) -> &[u16; C] {
+        &self.values
+    }
+
+    /// Latest value of channel `index`
+    pub fn value(&self, index: usize) -> u16 {
+        self.values[index]
+    }
+}
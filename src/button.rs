@@ -0,0 +1,727 @@
+//! Debounced button events
+//!
+//! [`Detector`] turns a sampled button level into events: [`Event::Pressed`]
+//! and [`Event::Released`] for every debounced edge, plus [`Event::Click`],
+//! [`Event::DoubleClick`], [`Event::LongPress`] and auto-repeat
+//! [`Event::Repeat`] derived from their timing. It only needs the level and a
+//! millisecond timestamp, so it can be driven from a recorded timeline as
+//! well as from a pin.
+//!
+//! [`Button`] wraps any `embedded_hal` `InputPin` (such as an esp-hal
+//! `Input`) and feeds its level to a detector:
+//!
+//! ```ignore
+//! let mut button = Button::new(input).with_long_press(1000);
+//! loop {
+//!     let now = Instant::now().duration_since_epoch().as_millis() as u32;
+//!     if let Some(event) = button.update(now)? {
+//!         // ...
+//!     }
+//! }
+//! ```
+//!
+//! A click is only reported once the double-click window has passed without
+//! a second press, so a double click never also reports a click. Presses
+//! that turned into a long press do not report a click either.
+//!
+//! [`Toggle`] turns events into a latching on/off state, and [`Buttons`]
+//! handles several buttons at once, reporting a [`GroupEvent::Chord`] when
+//! buttons are held together. Each [`Button`] has its own [`Polarity`], as
+//! some Grove-compatible buttons pull the signal low when pressed.
+
+use embedded_hal::digital::InputPin;
+
+use crate::queue::Queue;
+
+/// Something that happened to the button
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The button went down (debounced)
+    Pressed,
+    /// The button went up (debounced)
+    Released,
+    /// A short press without a second one following
+    Click,
+    /// Two short presses in quick succession
+    DoubleClick,
+    /// The button has been held for the long-press time
+    LongPress,
+    /// The button is still held after a long press (auto-repeat)
+    Repeat,
+}
+
+/// Timing thresholds of a [`Detector`], in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timings {
+    /// How long a level must be stable before it is accepted
+    pub debounce_ms: u32,
+    /// Longest gap between the release of the first click and the second
+    /// press of a double click; 0 disables double clicks
+    pub double_click_ms: u32,
+    /// How long the button must be held for a long press
+    pub long_press_ms: u32,
+    /// Interval of auto-repeat events after a long press; 0 disables them
+    pub repeat_ms: u32,
+}
+
+impl Default for Timings {
+    fn default() -> Self {
+        Self {
+            debounce_ms: 20,
+            double_click_ms: 300,
+            long_press_ms: 800,
+            repeat_ms: 0,
+        }
+    }
+}
+
+/// Events that can be produced by a single update: an edge plus the
+/// gesture it completes, and a click that was still pending
+const QUEUE_LEN: usize = 4;
+
+/// Button state machine fed with levels and timestamps
+#[derive(Clone, Debug)]
+pub struct Detector {
+    timings: Timings,
+    /// Last sampled level and when it changed
+    raw: bool,
+    raw_since_ms: u32,
+    /// Debounced level
+    pressed: bool,
+    pressed_at_ms: u32,
+    released_at_ms: u32,
+    /// A click was released and may still become a double click
+    awaiting_second: bool,
+    /// The current press is the second one of a double click
+    second_press: bool,
+    long_pressed: bool,
+    next_repeat_ms: u32,
+    queue: Queue<Event, QUEUE_LEN>,
+}
+
+impl Detector {
+    /// Create a released button with the given timings
+    pub fn new(timings: Timings) -> Self {
+        Self {
+            timings,
+            raw: false,
+            raw_since_ms: 0,
+            pressed: false,
+            pressed_at_ms: 0,
+            released_at_ms: 0,
+            awaiting_second: false,
+            second_press: false,
+            long_pressed: false,
+            next_repeat_ms: 0,
+            queue: Queue::new(),
+        }
+    }
+
+    /// Current timings
+    pub fn timings(&self) -> Timings {
+        self.timings
+    }
+
+    /// Replace the timings
+    pub fn set_timings(&mut self, timings: Timings) {
+        self.timings = timings;
+    }
+
+    /// Whether the button is down (debounced)
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    /// Whether nothing is in progress: released, stable, no click pending and
+    /// no events queued. An idle detector only changes on the next edge.
+    pub fn is_idle(&self) -> bool {
+        !self.pressed && self.raw == self.pressed && !self.awaiting_second && self.queue.is_empty()
+    }
+
+    /// How long the button has been held at `now_ms`, or 0 when released
+    pub fn held_ms(&self, now_ms: u32) -> u32 {
+        if self.pressed {
+            now_ms.wrapping_sub(self.pressed_at_ms)
+        } else {
+            0
+        }
+    }
+
+    /// Feed the level at `now_ms` and return the next event.
+    ///
+    /// Call this regularly, every few milliseconds. When one update produces
+    /// several events (e.g. `Released` and `DoubleClick`) the others are
+    /// returned by the following calls.
+    pub fn update(&mut self, pressed: bool, now_ms: u32) -> Option<Event> {
+        if pressed != self.raw {
+            self.raw = pressed;
+            self.raw_since_ms = now_ms;
+        }
+
+        let stable = now_ms.wrapping_sub(self.raw_since_ms) >= self.timings.debounce_ms;
+        if stable && self.raw != self.pressed {
+            self.pressed = self.raw;
+            if self.pressed {
+                self.on_press(now_ms);
+            } else {
+                self.on_release(now_ms);
+            }
+        } else if self.pressed {
+            self.while_pressed(now_ms);
+        } else {
+            self.while_released(now_ms);
+        }
+
+        self.queue.pop()
+    }
+
+    fn on_press(&mut self, now_ms: u32) {
+        // A press after the double-click window does not pair with the click
+        self.while_released(now_ms);
+
+        self.second_press = self.awaiting_second;
+        self.awaiting_second = false;
+        self.long_pressed = false;
+        self.pressed_at_ms = now_ms;
+        self.push(Event::Pressed);
+    }
+
+    fn on_release(&mut self, now_ms: u32) {
+        self.push(Event::Released);
+        self.released_at_ms = now_ms;
+
+        if self.long_pressed {
+            // Long presses are not clicks
+        } else if self.second_press {
+            self.push(Event::DoubleClick);
+        } else if self.timings.double_click_ms == 0 {
+            self.push(Event::Click);
+        } else {
+            self.awaiting_second = true;
+        }
+        self.second_press = false;
+    }
+
+    fn while_pressed(&mut self, now_ms: u32) {
+        if !self.long_pressed {
+            if now_ms.wrapping_sub(self.pressed_at_ms) >= self.timings.long_press_ms {
+                self.long_pressed = true;
+                self.second_press = false;
+                self.next_repeat_ms = now_ms.wrapping_add(self.timings.repeat_ms);
+                self.push(Event::LongPress);
+            }
+            return;
+        }
+
+        // Signed difference so the comparison survives timestamp wrap-around
+        let repeat_due = now_ms.wrapping_sub(self.next_repeat_ms) as i32 >= 0;
+        if self.timings.repeat_ms > 0 && repeat_due {
+            self.next_repeat_ms = self.next_repeat_ms.wrapping_add(self.timings.repeat_ms);
+            self.push(Event::Repeat);
+        }
+    }
+
+    fn while_released(&mut self, now_ms: u32) {
+        if self.awaiting_second
+            && now_ms.wrapping_sub(self.released_at_ms) > self.timings.double_click_ms
+        {
+            self.awaiting_second = false;
+            self.push(Event::Click);
+        }
+    }
+
+    fn push(&mut self, event: Event) {
+        // The queue only overflows if events are never read
+        self.queue.push(event);
+    }
+}
+
+impl Default for Detector {
+    fn default() -> Self {
+        Self::new(Timings::default())
+    }
+}
+
+/// Latching on/off state flipped by a button event, like a push-on/push-off
+/// switch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Toggle {
+    on: bool,
+    trigger: Event,
+}
+
+impl Toggle {
+    /// An off toggle flipped by every `trigger` event, e.g. [`Event::Pressed`]
+    /// to latch immediately or [`Event::Click`] to ignore long presses
+    pub const fn new(trigger: Event) -> Self {
+        Self { on: false, trigger }
+    }
+
+    /// Feed an event; returns true if the state changed
+    pub fn handle(&mut self, event: Event) -> bool {
+        if event == self.trigger {
+            self.on = !self.on;
+        }
+        event == self.trigger
+    }
+
+    /// Whether the toggle is on
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Force the state
+    pub fn set(&mut self, on: bool) {
+        self.on = on;
+    }
+}
+
+/// Which pin level means "pressed"
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Polarity {
+    /// Pressed reads high (Grove buttons, with a pull-down)
+    #[default]
+    ActiveHigh,
+    /// Pressed reads low (buttons to ground, with a pull-up)
+    ActiveLow,
+}
+
+impl Polarity {
+    /// Whether a pin reading `high` is pressed
+    pub fn is_pressed(self, high: bool) -> bool {
+        match self {
+            Polarity::ActiveHigh => high,
+            Polarity::ActiveLow => !high,
+        }
+    }
+}
+
+/// A button on an input pin
+pub struct Button<P> {
+    pin: P,
+    polarity: Polarity,
+    detector: Detector,
+}
+
+impl<P: InputPin> Button<P> {
+    /// Wrap `pin` with the default timings
+    pub fn new(pin: P) -> Self {
+        Self {
+            pin,
+            polarity: Polarity::default(),
+            detector: Detector::default(),
+        }
+    }
+
+    /// Set which level means pressed (default active-high)
+    pub fn with_polarity(mut self, polarity: Polarity) -> Self {
+        self.polarity = polarity;
+        self
+    }
+
+    /// Set the debounce time
+    pub fn with_debounce(mut self, debounce_ms: u32) -> Self {
+        self.detector.timings.debounce_ms = debounce_ms;
+        self
+    }
+
+    /// Set the double-click window (0 disables double clicks)
+    pub fn with_double_click(mut self, double_click_ms: u32) -> Self {
+        self.detector.timings.double_click_ms = double_click_ms;
+        self
+    }
+
+    /// Set the long-press time
+    pub fn with_long_press(mut self, long_press_ms: u32) -> Self {
+        self.detector.timings.long_press_ms = long_press_ms;
+        self
+    }
+
+    /// Enable auto-repeat after a long press every `repeat_ms` (0 disables it)
+    pub fn with_repeat(mut self, repeat_ms: u32) -> Self {
+        self.detector.timings.repeat_ms = repeat_ms;
+        self
+    }
+
+    /// Read the pin at `now_ms` and return the next event
+    pub fn update(&mut self, now_ms: u32) -> Result<Option<Event>, P::Error> {
+        let pressed = self.polarity.is_pressed(self.pin.is_high()?);
+        Ok(self.detector.update(pressed, now_ms))
+    }
+
+    /// Whether the button is down (debounced)
+    pub fn is_pressed(&self) -> bool {
+        self.detector.is_pressed()
+    }
+
+    /// The underlying state machine
+    pub fn detector(&self) -> &Detector {
+        &self.detector
+    }
+
+    /// Release the pin
+    pub fn release(self) -> P {
+        self.pin
+    }
+}
+
+/// An event from a group of buttons
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupEvent {
+    /// An event of the button at this index
+    Button(usize, Event),
+    /// Buttons held together, as a bit mask of their indices
+    Chord(u8),
+}
+
+/// Events that can be waiting in a [`Chords`] tracker
+const GROUP_QUEUE_LEN: usize = 8;
+
+/// Chord detection across up to eight buttons.
+///
+/// A chord is reported when a button is pressed while others are held.
+/// Clicks, long presses and repeats of the buttons in a chord are dropped,
+/// so pressing two buttons together does not also trigger their single
+/// actions; `Pressed` and `Released` are always passed through.
+#[derive(Clone, Debug)]
+pub struct Chords<const N: usize> {
+    held: u8,
+    in_chord: u8,
+    queue: Queue<GroupEvent, GROUP_QUEUE_LEN>,
+}
+
+impl<const N: usize> Default for Chords<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Chords<N> {
+    /// Create a tracker with no buttons held
+    pub fn new() -> Self {
+        assert!(N <= 8, "at most 8 buttons are supported");
+        Self {
+            held: 0,
+            in_chord: 0,
+            queue: Queue::new(),
+        }
+    }
+
+    /// Bit mask of the buttons currently held
+    pub fn held(&self) -> u8 {
+        self.held
+    }
+
+    /// Feed an event of button `index`
+    pub fn handle(&mut self, index: usize, event: Event) {
+        let bit = 1 << index;
+
+        match event {
+            Event::Pressed => {
+                // A fresh press on its own ends this button's part in a chord
+                if self.held == 0 {
+                    self.in_chord &= !bit;
+                }
+                self.held |= bit;
+                self.push(GroupEvent::Button(index, event));
+                if self.held.count_ones() > 1 {
+                    self.in_chord |= self.held;
+                    self.push(GroupEvent::Chord(self.held));
+                }
+            }
+            Event::Released => {
+                self.held &= !bit;
+                self.push(GroupEvent::Button(index, event));
+            }
+            _ if self.in_chord & bit != 0 => {}
+            _ => self.push(GroupEvent::Button(index, event)),
+        }
+    }
+
+    /// Take the next event
+    pub fn pop(&mut self) -> Option<GroupEvent> {
+        self.queue.pop()
+    }
+
+    fn push(&mut self, event: GroupEvent) {
+        self.queue.push(event);
+    }
+}
+
+/// Several buttons polled together, with chord detection
+pub struct Buttons<P, const N: usize> {
+    buttons: [Button<P>; N],
+    chords: Chords<N>,
+}
+
+impl<P: InputPin, const N: usize> Buttons<P, N> {
+    /// Group up to eight buttons; their index is their position in `buttons`
+    pub fn new(buttons: [Button<P>; N]) -> Self {
+        Self {
+            buttons,
+            chords: Chords::new(),
+        }
+    }
+
+    /// Read every button at `now_ms` and return the next event
+    pub fn update(&mut self, now_ms: u32) -> Result<Option<GroupEvent>, P::Error> {
+        for (index, button) in self.buttons.iter_mut().enumerate() {
+            if let Some(event) = button.update(now_ms)? {
+                self.chords.handle(index, event);
+            }
+        }
+        Ok(self.chords.pop())
+    }
+
+    /// The button at `index`
+    pub fn button(&self, index: usize) -> &Button<P> {
+        &self.buttons[index]
+    }
+
+    /// Bit mask of the buttons currently held
+    pub fn held(&self) -> u8 {
+        self.chords.held()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{cell::Cell, convert::Infallible};
+
+    use super::*;
+
+    /// Input pin whose level is set by the test
+    struct Pin<'a>(&'a Cell<bool>);
+
+    impl embedded_hal::digital::ErrorType for Pin<'_> {
+        type Error = Infallible;
+    }
+
+    impl InputPin for Pin<'_> {
+        fn is_high(&mut self) -> Result<bool, Infallible> {
+            Ok(self.0.get())
+        }
+
+        fn is_low(&mut self) -> Result<bool, Infallible> {
+            Ok(!self.0.get())
+        }
+    }
+
+    /// Sample `detector` every millisecond from `start_ms` for `duration_ms`,
+    /// pressed during the `presses` intervals; returns the events with their
+    /// times relative to `start_ms`
+    fn run(
+        timings: Timings,
+        start_ms: u32,
+        presses: &[(u32, u32)],
+        duration_ms: u32,
+    ) -> Vec<(u32, Event)> {
+        let mut detector = Detector::new(timings);
+        (0..duration_ms)
+            .filter_map(|t| {
+                let pressed = presses.iter().any(|&(down, up)| (down..up).contains(&t));
+                detector
+                    .update(pressed, start_ms.wrapping_add(t))
+                    .map(|event| (t, event))
+            })
+            .collect()
+    }
+
+    /// Name, timings, pressed intervals, length and expected events
+    type Timeline = (
+        &'static str,
+        Timings,
+        &'static [(u32, u32)],
+        u32,
+        &'static [(u32, Event)],
+    );
+
+    const DEFAULTS: Timings = Timings {
+        debounce_ms: 20,
+        double_click_ms: 300,
+        long_press_ms: 800,
+        repeat_ms: 0,
+    };
+
+    const REPEAT: Timings = Timings {
+        repeat_ms: 100,
+        ..DEFAULTS
+    };
+
+    const TIMELINES: [Timeline; 6] = {
+        use Event::*;
+        [
+            (
+                "bounce shorter than debounce",
+                DEFAULTS,
+                &[(100, 110)],
+                1000,
+                &[],
+            ),
+            (
+                "bounce while held",
+                DEFAULTS,
+                &[(100, 200), (205, 500)],
+                1000,
+                &[(120, Pressed), (520, Released), (821, Click)],
+            ),
+            (
+                "click after the double-click window",
+                DEFAULTS,
+                &[(100, 200)],
+                1000,
+                &[(120, Pressed), (220, Released), (521, Click)],
+            ),
+            (
+                "double click without click",
+                DEFAULTS,
+                &[(100, 200), (300, 400)],
+                1500,
+                &[
+                    (120, Pressed),
+                    (220, Released),
+                    (320, Pressed),
+                    (420, Released),
+                    (421, DoubleClick),
+                ],
+            ),
+            (
+                "long press without click",
+                DEFAULTS,
+                &[(100, 1200)],
+                2000,
+                &[(120, Pressed), (920, LongPress), (1220, Released)],
+            ),
+            (
+                "repeat cadence",
+                REPEAT,
+                &[(100, 1150)],
+                2000,
+                &[
+                    (120, Pressed),
+                    (920, LongPress),
+                    (1020, Repeat),
+                    (1120, Repeat),
+                    (1170, Released),
+                ],
+            ),
+        ]
+    };
+
+    #[test]
+    fn timelines() {
+        for (name, timings, presses, duration_ms, expected) in TIMELINES {
+            assert_eq!(run(timings, 0, presses, duration_ms), expected, "{name}");
+        }
+    }
+
+    #[test]
+    fn timelines_across_timestamp_wrap_around() {
+        for start_ms in [u32::MAX - 1000, u32::MAX - 150] {
+            for (name, timings, presses, duration_ms, expected) in TIMELINES {
+                assert_eq!(
+                    run(timings, start_ms, presses, duration_ms),
+                    expected,
+                    "{name} from {start_ms}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn idle_after_events_are_read() {
+        let mut detector = Detector::default();
+        assert!(detector.is_idle());
+        for t in 0..1000 {
+            detector.update((100..200).contains(&t), t);
+        }
+        assert!(detector.is_idle());
+    }
+
+    #[test]
+    fn toggle() {
+        let mut toggle = Toggle::new(Event::Click);
+        assert!(!toggle.handle(Event::Pressed));
+        assert!(!toggle.handle(Event::LongPress));
+        assert!(!toggle.is_on());
+        assert!(toggle.handle(Event::Click));
+        assert!(toggle.is_on());
+        assert!(toggle.handle(Event::Click));
+        assert!(!toggle.is_on());
+        toggle.set(true);
+        assert!(toggle.is_on());
+    }
+
+    #[test]
+    fn chord_suppresses_single_actions() {
+        use Event::*;
+        let mut chords = Chords::<3>::new();
+        chords.handle(0, Pressed);
+        chords.handle(2, Pressed);
+        assert_eq!(chords.held(), 0b101);
+        chords.handle(0, LongPress);
+        chords.handle(0, Released);
+        chords.handle(2, Released);
+        chords.handle(2, Click);
+        assert_eq!(chords.held(), 0);
+        // A later press on its own acts normally again
+        chords.handle(2, Pressed);
+        chords.handle(2, Released);
+        chords.handle(2, Click);
+
+        let events: Vec<_> = core::iter::from_fn(|| chords.pop()).collect();
+        assert_eq!(
+            events,
+            [
+                GroupEvent::Button(0, Pressed),
+                GroupEvent::Button(2, Pressed),
+                GroupEvent::Chord(0b101),
+                GroupEvent::Button(0, Released),
+                GroupEvent::Button(2, Released),
+                GroupEvent::Button(2, Pressed),
+                GroupEvent::Button(2, Released),
+                GroupEvent::Button(2, Click),
+            ]
+        );
+    }
+
+    #[test]
+    fn active_low_buttons_in_a_chord() {
+        use Event::*;
+        let levels = [Cell::new(true), Cell::new(true)];
+        let mut buttons = Buttons::new([
+            Button::new(Pin(&levels[0])).with_polarity(Polarity::ActiveLow),
+            Button::new(Pin(&levels[1])).with_polarity(Polarity::ActiveLow),
+        ]);
+
+        // Button 1 joins 50 ms after button 0; later button 0 is clicked alone
+        let presses: [&[(u32, u32)]; 2] = [&[(100, 400), (1000, 1100)], &[(150, 400)]];
+        let mut events = Vec::new();
+        for t in 0..2000 {
+            for (level, presses) in levels.iter().zip(presses) {
+                level.set(!presses.iter().any(|&(down, up)| (down..up).contains(&t)));
+            }
+            if let Some(event) = buttons.update(t).unwrap() {
+                events.push((t, event));
+            }
+            if t == 300 {
+                assert_eq!(buttons.held(), 0b11);
+                assert!(buttons.button(1).is_pressed());
+            }
+        }
+
+        assert_eq!(
+            events,
+            [
+                (120, GroupEvent::Button(0, Pressed)),
+                (170, GroupEvent::Button(1, Pressed)),
+                (171, GroupEvent::Chord(0b11)),
+                (420, GroupEvent::Button(0, Released)),
+                (421, GroupEvent::Button(1, Released)),
+                (1020, GroupEvent::Button(0, Pressed)),
+                (1120, GroupEvent::Button(0, Released)),
+                (1421, GroupEvent::Button(0, Click)),
+            ]
+        );
+    }
+}
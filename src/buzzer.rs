@@ -0,0 +1,294 @@
+//! Tone and melody player for the Grove Buzzer
+//!
+//! [`Buzzer`] drives a piezo from an LEDC channel and changes the pitch by
+//! reconfiguring the LEDC timer frequency for every note. Volume is set via
+//! the PWM duty cycle (a square wave at 50% duty is the loudest).
+//!
+//! The LEDC channel keeps a shared reference to the timer it was configured
+//! with, so the buzzer needs a second handle to the same hardware timer to
+//! change its frequency:
+//!
+//! ```ignore
+//! let mut lstimer0 = ledc.timer::<LowSpeed>(timer::Number::Timer0);
+//! lstimer0.configure(Buzzer::timer_config(Rate::from_hz(440))).unwrap();
+//!
+//! let mut channel = ledc.channel(channel::Number::Channel0, peripherals.GPIO2);
+//! channel.configure(channel::config::Config {
+//!     timer: &lstimer0,
+//!     duty_pct: 0,
+//!     drive_mode: DriveMode::PushPull,
+//! }).unwrap();
+//!
+//! let mut buzzer = Buzzer::new(channel, ledc.timer(timer::Number::Timer0));
+//! ```
+
+use embedded_hal::delay::DelayNs;
+use esp_hal::{
+    ledc::{
+        channel::{self, ChannelHW, ChannelIFace},
+        timer::{self, TimerIFace},
+        LowSpeed,
+    },
+    time::Rate,
+};
+
+use crate::{
+    notes::{Note, Pitch},
+    rtttl::{self, Rtttl},
+    tracker::{self, Tracker, DEFAULT_ARPEGGIO_MS},
+};
+
+/// Lowest frequency the timer can produce with [`Buzzer::DUTY`] resolution
+/// from the 80 MHz APB clock. Lower notes are raised by whole octaves.
+pub const MIN_FREQUENCY_HZ: u32 = 20;
+
+/// Highest frequency the timer can produce with [`Buzzer::DUTY`] resolution
+pub const MAX_FREQUENCY_HZ: u32 = 19_500;
+
+/// Errors from the underlying LEDC timer or channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// The timer could not be configured for the requested frequency
+    Timer(timer::Error),
+    /// The channel rejected the duty cycle
+    Channel(channel::Error),
+    /// A ringtone passed to [`Buzzer::play_rtttl`] is malformed
+    Rtttl(rtttl::Error),
+    /// A pattern passed to [`Buzzer::play_tracker`] is malformed
+    Tracker(tracker::Error),
+}
+
+impl From<timer::Error> for Error {
+    fn from(e: timer::Error) -> Self {
+        Error::Timer(e)
+    }
+}
+
+impl From<channel::Error> for Error {
+    fn from(e: channel::Error) -> Self {
+        Error::Channel(e)
+    }
+}
+
+impl From<rtttl::Error> for Error {
+    fn from(e: rtttl::Error) -> Self {
+        Error::Rtttl(e)
+    }
+}
+
+impl From<tracker::Error> for Error {
+    fn from(e: tracker::Error) -> Self {
+        Error::Tracker(e)
+    }
+}
+
+/// Piezo buzzer on an LEDC low-speed channel
+pub struct Buzzer<'a> {
+    channel: channel::Channel<'a, LowSpeed>,
+    timer: timer::Timer<'a, LowSpeed>,
+    volume: u8,
+    tempo: u16,
+    arpeggio_ms: u32,
+}
+
+impl<'a> Buzzer<'a> {
+    /// Duty resolution used for the buzzer timer.
+    ///
+    /// 12 bits allow frequencies from ~20 Hz up to ~19.5 kHz.
+    pub const DUTY: timer::config::Duty = timer::config::Duty::Duty12Bit;
+
+    /// Timer configuration for the buzzer at `frequency`
+    pub fn timer_config(frequency: Rate) -> timer::config::Config<timer::LSClockSource> {
+        timer::config::Config {
+            duty: Self::DUTY,
+            clock_source: timer::LSClockSource::APBClk,
+            frequency,
+        }
+    }
+
+    /// Create a buzzer from a configured channel and a second handle to its timer
+    pub fn new(channel: channel::Channel<'a, LowSpeed>, timer: timer::Timer<'a, LowSpeed>) -> Self {
+        Self {
+            channel,
+            timer,
+            volume: 100,
+            tempo: 120,
+            arpeggio_ms: DEFAULT_ARPEGGIO_MS,
+        }
+    }
+
+    /// Set the volume in percent (0-100)
+    pub fn set_volume(&mut self, volume: u8) {
+        self.volume = volume.min(100);
+    }
+
+    /// Current volume in percent
+    pub fn volume(&self) -> u8 {
+        self.volume
+    }
+
+    /// Set the tempo in quarter notes per minute
+    pub fn set_tempo(&mut self, bpm: u16) {
+        self.tempo = bpm.max(1);
+    }
+
+    /// Current tempo in quarter notes per minute
+    pub fn tempo(&self) -> u16 {
+        self.tempo
+    }
+
+    /// Set how long each note of a chord sounds while arpeggiating
+    pub fn set_arpeggio_rate(&mut self, arpeggio_ms: u32) {
+        self.arpeggio_ms = arpeggio_ms.max(1);
+    }
+
+    /// Time each chord note sounds while arpeggiating, in milliseconds
+    pub fn arpeggio_rate(&self) -> u32 {
+        self.arpeggio_ms
+    }
+
+    /// Start a continuous tone at `frequency_hz`
+    pub fn tone(&mut self, frequency_hz: u32) -> Result<(), Error> {
+        if frequency_hz == 0 {
+            return self.silence();
+        }
+
+        let mut frequency_hz = frequency_hz.min(MAX_FREQUENCY_HZ);
+        while frequency_hz < MIN_FREQUENCY_HZ {
+            frequency_hz *= 2;
+        }
+
+        self.timer
+            .configure(Self::timer_config(Rate::from_hz(frequency_hz)))?;
+
+        // Full volume is a 50% square wave. The raw duty keeps every volume
+        // step audible, where whole percent duty would round 1% down to 0.
+        let duty_range = 1u32 << Self::DUTY as u32;
+        self.channel
+            .set_duty_hw(duty_range * self.volume as u32 / 200);
+        Ok(())
+    }
+
+    /// Stop any tone
+    pub fn silence(&mut self) -> Result<(), Error> {
+        Ok(self.channel.set_duty(0)?)
+    }
+
+    /// Play a tone for `duration_ms`, then stop
+    pub fn beep<D: DelayNs>(
+        &mut self,
+        frequency_hz: u32,
+        duration_ms: u32,
+        delay: &mut D,
+    ) -> Result<(), Error> {
+        self.tone(frequency_hz)?;
+        delay.delay_ms(duration_ms);
+        self.silence()
+    }
+
+    /// Play a single note (or rest) at the current tempo
+    pub fn play_note<D: DelayNs>(&mut self, note: &Note, delay: &mut D) -> Result<(), Error> {
+        let duration = note.duration_ms(self.tempo);
+
+        match note.frequency() {
+            Some(frequency) => {
+                // Leave a short gap so repeated notes are heard separately
+                let gap = duration / 10;
+                self.tone(libm::roundf(frequency) as u32)?;
+                delay.delay_ms(duration - gap);
+                self.silence()?;
+                delay.delay_ms(gap);
+            }
+            None => {
+                self.silence()?;
+                delay.delay_ms(duration);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Play a sequence of notes at the current tempo
+    pub fn play<D: DelayNs>(&mut self, notes: &[Note], delay: &mut D) -> Result<(), Error> {
+        for note in notes {
+            self.play_note(note, delay)?;
+        }
+        self.silence()
+    }
+
+    /// Play an RTTTL ringtone, e.g. `"Beep:d=8,o=6,b=180:c,e,g"`.
+    ///
+    /// The ringtone's tempo is used while playing; the buzzer's own tempo is
+    /// restored afterwards. Notes are played until the first malformed one.
+    pub fn play_rtttl<D: DelayNs>(&mut self, ringtone: &str, delay: &mut D) -> Result<(), Error> {
+        let ringtone = Rtttl::parse(ringtone)?;
+
+        let tempo = self.tempo;
+        self.tempo = ringtone.bpm();
+
+        let result = ringtone
+            .notes()
+            .try_for_each(|note| self.play_note(&note?, delay));
+
+        self.tempo = tempo;
+        self.silence()?;
+        result
+    }
+
+    /// Sound a chord for `duration_ms` by cycling through its tones.
+    ///
+    /// ```ignore
+    /// buzzer.play_chord(Chord::Major.tones(Pitch::C, 5), 500, &mut delay)?;
+    /// ```
+    pub fn play_chord<I, D>(
+        &mut self,
+        tones: I,
+        duration_ms: u32,
+        delay: &mut D,
+    ) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = (Pitch, u8)>,
+        I::IntoIter: Clone,
+        D: DelayNs,
+    {
+        let tones = tones.into_iter();
+        if tones.clone().next().is_none() {
+            self.silence()?;
+            delay.delay_ms(duration_ms);
+            return Ok(());
+        }
+
+        let mut remaining = duration_ms;
+        for (pitch, octave) in tones.cycle() {
+            if remaining == 0 {
+                break;
+            }
+            let slice = self.arpeggio_ms.min(remaining);
+            self.tone(libm::roundf(pitch.frequency(octave)) as u32)?;
+            delay.delay_ms(slice);
+            remaining -= slice;
+        }
+
+        self.silence()
+    }
+
+    /// Play a tracker pattern, blocking until it ends.
+    ///
+    /// The pattern uses its own arpeggio rate. Steps are played until the
+    /// first malformed row.
+    pub fn play_tracker<D: DelayNs, const C: usize>(
+        &mut self,
+        pattern: &Tracker<C>,
+        delay: &mut D,
+    ) -> Result<(), Error> {
+        let result = pattern.steps().try_for_each(|step| {
+            let step = step?;
+            self.tone(step.frequency_hz)?;
+            delay.delay_ms(step.duration_ms);
+            Ok(())
+        });
+
+        self.silence()?;
+        result
+    }
+}
@@ -0,0 +1,168 @@
+//! Color correction for RGB LED frames
+//!
+//! Colors computed for the eye (such as `hsv2rgb` output) look washed out
+//! and uneven on LEDs, whose light output is linear in the PWM duty.
+//! [`Correction`] post-processes each frame before it is written:
+//!
+//! 1. gamma correction, mapping perceived levels to linear duty,
+//! 2. white point: per-channel scaling for the LED's own color balance and a
+//!    color temperature (see [`kelvin_to_rgb`]),
+//! 3. global brightness,
+//! 4. temporal dithering back to 8 bits.
+//!
+//! Steps 1-3 are done with 16-bit precision. Dithering spreads the fraction
+//! left over when rounding to 8 bits across frames, so dim colors keep their
+//! hue and smooth fades instead of collapsing onto the few lowest levels. It
+//! works best at high frame rates (100 fps or more) where the alternation
+//! between neighboring levels does not show as flicker.
+//!
+//! ```ignore
+//! let mut correction = Correction::<15>::new().with_temperature(4000);
+//! correction.set_brightness(64);
+//! effect.render(now_ms(), &mut frame);
+//! correction.apply(&mut frame);
+//! leds.write(frame.iter().cloned())?;
+//! ```
+
+use smart_leds::RGB8;
+
+/// Default gamma, close to the sRGB curve
+pub const DEFAULT_GAMMA: f32 = 2.2;
+
+/// Color of a black body at `kelvin` (1000-40000 K) as RGB.
+///
+/// Uses Tanner Helland's fit of the CIE 1964 color matching data; 6500 K is
+/// close to neutral white, lower values are warmer and higher ones cooler.
+pub fn kelvin_to_rgb(kelvin: u32) -> RGB8 {
+    let t = kelvin.clamp(1000, 40_000) as f32 / 100.0;
+    let channel = |value: f32| value.clamp(0.0, 255.0) as u8;
+
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.69873 * libm::powf(t - 60.0, -0.13320476)
+    };
+    let g = if t <= 66.0 {
+        99.4708 * libm::logf(t) - 161.11957
+    } else {
+        288.12216 * libm::powf(t - 60.0, -0.07551485)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.51773 * libm::logf(t - 10.0) - 305.0448
+    };
+
+    RGB8::new(channel(r), channel(g), channel(b))
+}
+
+/// Gamma, white point, brightness and dithering for frames of up to `N`
+/// pixels
+pub struct Correction<const N: usize> {
+    /// Linear 16-bit level for each 8-bit input level
+    gamma: [u16; 256],
+    /// LED color balance
+    correction: RGB8,
+    /// Color temperature as RGB
+    temperature: RGB8,
+    brightness: u8,
+    dithering: bool,
+    /// Fraction below 8 bits carried over to the next frame, per channel
+    error: [[u8; 3]; N],
+}
+
+impl<const N: usize> Correction<N> {
+    /// Create a correction with [`DEFAULT_GAMMA`], neutral white and full
+    /// brightness
+    pub fn new() -> Self {
+        let mut correction = Self {
+            gamma: [0; 256],
+            correction: RGB8::new(255, 255, 255),
+            temperature: RGB8::new(255, 255, 255),
+            brightness: u8::MAX,
+            dithering: true,
+            error: [[0; 3]; N],
+        };
+        correction.set_gamma(DEFAULT_GAMMA);
+        correction
+    }
+
+    /// Use `gamma` instead of [`DEFAULT_GAMMA`]; 1.0 disables the correction
+    pub fn with_gamma(mut self, gamma: f32) -> Self {
+        self.set_gamma(gamma);
+        self
+    }
+
+    /// Scale the channels for the LED's color balance, e.g. `(255, 176, 240)`
+    /// for typical 5050 LEDs whose green and blue are too strong
+    pub fn with_correction(mut self, correction: RGB8) -> Self {
+        self.correction = correction;
+        self
+    }
+
+    /// Tint white to a color temperature in Kelvin
+    pub fn with_temperature(mut self, kelvin: u32) -> Self {
+        self.temperature = kelvin_to_rgb(kelvin);
+        self
+    }
+
+    /// Round to 8 bits without carrying the fraction to the next frame, for
+    /// low frame rates where dithering would flicker
+    pub fn without_dithering(mut self) -> Self {
+        self.dithering = false;
+        self
+    }
+
+    /// Global brightness (0-255)
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Set the global brightness (0-255), applied after gamma correction
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = brightness;
+    }
+
+    /// Correct `frame` in place. Pixels beyond `N` are not dithered.
+    pub fn apply(&mut self, frame: &mut [RGB8]) {
+        let white = [
+            self.correction.r as u32 * self.temperature.r as u32,
+            self.correction.g as u32 * self.temperature.g as u32,
+            self.correction.b as u32 * self.temperature.b as u32,
+        ];
+
+        for (i, pixel) in frame.iter_mut().enumerate() {
+            let mut channels = [pixel.r, pixel.g, pixel.b];
+            for (c, channel) in channels.iter_mut().enumerate() {
+                // 16-bit linear level, scaled by white point and brightness
+                let level = self.gamma[*channel as usize] as u32 * white[c] / (255 * 255)
+                    * self.brightness as u32
+                    / 255;
+
+                *channel = match self.error.get_mut(i) {
+                    Some(error) if self.dithering => {
+                        let total = level + error[c] as u32;
+                        error[c] = total as u8;
+                        (total >> 8).min(255) as u8
+                    }
+                    _ => ((level + 0x80) >> 8).min(255) as u8,
+                };
+            }
+            *pixel = RGB8::new(channels[0], channels[1], channels[2]);
+        }
+    }
+
+    fn set_gamma(&mut self, gamma: f32) {
+        for (level, linear) in self.gamma.iter_mut().enumerate() {
+            *linear = libm::roundf(libm::powf(level as f32 / 255.0, gamma) * 65535.0) as u16;
+        }
+    }
+}
+
+impl<const N: usize> Default for Correction<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
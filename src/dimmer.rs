@@ -0,0 +1,203 @@
+//! Dimmable single-color LED with gamma-corrected fades
+//!
+//! The eye does not see light linearly: a PWM duty cycle of 50% looks much
+//! brighter than "half". [`Dimmer`] takes brightness on a perceptual 0-255
+//! scale and converts it to a duty cycle with the CIE 1931 lightness curve,
+//! so fades look even from start to end.
+//!
+//! It works with any `embedded_hal` `SetDutyCycle` output, such as an LEDC
+//! channel. Use a high duty resolution (e.g. 13 bits) so the dimmest levels
+//! still have distinct steps.
+//!
+//! Fades and breathing are advanced by [`Dimmer::update`] with a millisecond
+//! timestamp, so they run without blocking.
+
+use core::f32::consts::PI;
+
+use embedded_hal::pwm::SetDutyCycle;
+
+/// Relative luminance (0.0-1.0) for a perceived `brightness` (0-255), using
+/// the CIE 1931 lightness formula
+pub fn cie1931(brightness: u8) -> f32 {
+    let lightness = brightness as f32 * 100.0 / 255.0;
+    if lightness <= 8.0 {
+        lightness / 903.3
+    } else {
+        let t = (lightness + 16.0) / 116.0;
+        t * t * t
+    }
+}
+
+/// Shape of a fade over time
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Easing {
+    /// Constant speed
+    #[default]
+    Linear,
+    /// Starts and ends slowly (cosine)
+    EaseInOut,
+}
+
+impl Easing {
+    /// Progress (0.0-1.0) at fraction `t` (0.0-1.0) of the fade
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut => 0.5 * (1.0 - libm::cosf(PI * t)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Effect {
+    Steady,
+    Fade {
+        from: u8,
+        to: u8,
+        duration_ms: u32,
+        easing: Easing,
+    },
+    Breathing {
+        min: u8,
+        max: u8,
+        period_ms: u32,
+    },
+}
+
+/// A PWM-dimmed LED with perceptual brightness
+pub struct Dimmer<P> {
+    pwm: P,
+    gamma: bool,
+    inverted: bool,
+    brightness: u8,
+    effect: Effect,
+    /// When the effect started; set on the first update after a change
+    started_ms: Option<u32>,
+}
+
+impl<P: SetDutyCycle> Dimmer<P> {
+    /// Wrap a PWM output; the LED starts off
+    pub fn new(pwm: P) -> Self {
+        Self {
+            pwm,
+            gamma: true,
+            inverted: false,
+            brightness: 0,
+            effect: Effect::Steady,
+            started_ms: None,
+        }
+    }
+
+    /// Map brightness to duty linearly instead of with the CIE 1931 curve
+    pub fn without_gamma(mut self) -> Self {
+        self.gamma = false;
+        self
+    }
+
+    /// Invert the duty cycle for LEDs that light when the pin is low, such
+    /// as the onboard LED on GPIO15
+    pub fn with_inverted(mut self) -> Self {
+        self.inverted = true;
+        self
+    }
+
+    /// Current perceived brightness (0-255)
+    pub fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Whether a fade or breathing effect is running
+    pub fn is_animating(&self) -> bool {
+        self.effect != Effect::Steady
+    }
+
+    /// Set the brightness immediately, stopping any effect
+    pub fn set_brightness(&mut self, brightness: u8) -> Result<(), P::Error> {
+        self.effect = Effect::Steady;
+        self.apply(brightness)
+    }
+
+    /// Fade from the current brightness to `target` over `duration_ms`
+    pub fn fade_to(&mut self, target: u8, duration_ms: u32, easing: Easing) {
+        self.start(Effect::Fade {
+            from: self.brightness,
+            to: target,
+            duration_ms,
+            easing,
+        });
+    }
+
+    /// Breathe between `min` and `max` brightness with a full cycle every
+    /// `period_ms`, until another brightness or effect is set
+    pub fn breathe(&mut self, min: u8, max: u8, period_ms: u32) {
+        self.start(Effect::Breathing {
+            min,
+            max,
+            period_ms: period_ms.max(1),
+        });
+    }
+
+    /// Advance the running effect to `now_ms`
+    pub fn update(&mut self, now_ms: u32) -> Result<(), P::Error> {
+        let started = *self.started_ms.get_or_insert(now_ms);
+        let elapsed = now_ms.wrapping_sub(started);
+
+        let brightness = match self.effect {
+            Effect::Steady => return Ok(()),
+            Effect::Fade {
+                from,
+                to,
+                duration_ms,
+                easing,
+            } => {
+                if elapsed >= duration_ms {
+                    self.effect = Effect::Steady;
+                    to
+                } else {
+                    let progress = easing.apply(elapsed as f32 / duration_ms as f32);
+                    lerp(from, to, progress)
+                }
+            }
+            Effect::Breathing {
+                min,
+                max,
+                period_ms,
+            } => {
+                let phase = 2.0 * PI * (elapsed % period_ms) as f32 / period_ms as f32;
+                lerp(min, max, 0.5 * (1.0 - libm::cosf(phase)))
+            }
+        };
+
+        self.apply(brightness)
+    }
+
+    /// Release the PWM output
+    pub fn release(self) -> P {
+        self.pwm
+    }
+
+    fn start(&mut self, effect: Effect) {
+        self.effect = effect;
+        self.started_ms = None;
+    }
+
+    fn apply(&mut self, brightness: u8) -> Result<(), P::Error> {
+        self.brightness = brightness;
+
+        let level = if self.gamma {
+            cie1931(brightness)
+        } else {
+            brightness as f32 / 255.0
+        };
+
+        let max = self.pwm.max_duty_cycle();
+        let duty = libm::roundf(level * max as f32) as u16;
+        self.pwm
+            .set_duty_cycle(if self.inverted { max - duty } else { duty })
+    }
+}
+
+fn lerp(from: u8, to: u8, progress: f32) -> u8 {
+    libm::roundf(from as f32 + (to as f32 - from as f32) * progress) as u8
+}
@@ -0,0 +1,201 @@
+//! Interrupt-driven GPIO edge events
+//!
+//! Instead of polling inputs every few milliseconds, [`EdgeInputs`] listens
+//! for edges on a set of esp-hal `Input`s. The GPIO interrupt handler calls
+//! [`EdgeInputs::on_interrupt`], which records every level change as an
+//! [`Edge`] in an [`EdgeQueue`] that the main loop drains.
+//!
+//! Between events the chip can be put into light sleep: once the queue is
+//! drained, [`EdgeInputs::prepare_sleep`] arms level wake-ups for the
+//! opposite of each input's last recorded level, and [`EdgeInputs::resume`]
+//! re-arms the edge interrupts afterwards, queuing the edge that woke the
+//! chip.
+//!
+//! ```ignore
+//! static INPUTS: Mutex<RefCell<Option<EdgeInputs<'static, 1>>>> = Mutex::new(RefCell::new(None));
+//! static EDGES: EdgeQueue<16> = EdgeQueue::new();
+//!
+//! #[handler]
+//! fn gpio_handler() {
+//!     critical_section::with(|cs| {
+//!         if let Some(inputs) = INPUTS.borrow_ref_mut(cs).as_mut() {
+//!             inputs.on_interrupt(&EDGES, now_ms());
+//!         }
+//!     });
+//! }
+//! ```
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+use esp_hal::gpio::{Event, Input, WakeConfigError, WakeEvent};
+
+use crate::queue::Queue;
+
+/// A level change on one of the inputs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Edge {
+    /// Index of the input in [`EdgeInputs`]
+    pub input: usize,
+    /// Level after the change
+    pub high: bool,
+    /// When the change was seen, in milliseconds
+    pub timestamp_ms: u32,
+}
+
+/// Edges waiting to be read, and how many did not fit
+struct Pending<const N: usize> {
+    edges: Queue<Edge, N>,
+    dropped: u32,
+}
+
+/// Fixed-size queue of edges shared between an interrupt handler and the
+/// main loop. Every operation runs in a critical section.
+pub struct EdgeQueue<const N: usize> {
+    pending: Mutex<RefCell<Pending<N>>>,
+}
+
+impl<const N: usize> Default for EdgeQueue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> EdgeQueue<N> {
+    /// Create an empty queue, usable in a `static`
+    pub const fn new() -> Self {
+        Self {
+            pending: Mutex::new(RefCell::new(Pending {
+                edges: Queue::new(),
+                dropped: 0,
+            })),
+        }
+    }
+
+    /// Add an edge; returns false (and counts it as dropped) when full
+    pub fn push(&self, edge: Edge) -> bool {
+        critical_section::with(|cs| {
+            let mut pending = self.pending.borrow_ref_mut(cs);
+            let pushed = pending.edges.push(edge);
+            if !pushed {
+                pending.dropped = pending.dropped.wrapping_add(1);
+            }
+            pushed
+        })
+    }
+
+    /// Remove the oldest edge
+    pub fn pop(&self) -> Option<Edge> {
+        critical_section::with(|cs| self.pending.borrow_ref_mut(cs).edges.pop())
+    }
+
+    /// Whether no edges are waiting
+    pub fn is_empty(&self) -> bool {
+        critical_section::with(|cs| self.pending.borrow_ref(cs).edges.is_empty())
+    }
+
+    /// Number of edges lost because the queue was full
+    pub fn dropped(&self) -> u32 {
+        critical_section::with(|cs| self.pending.borrow_ref(cs).dropped)
+    }
+}
+
+/// A set of inputs reporting their edges through an [`EdgeQueue`]
+pub struct EdgeInputs<'d, const C: usize> {
+    inputs: [Input<'d>; C],
+    levels: [bool; C],
+}
+
+impl<'d, const C: usize> EdgeInputs<'d, C> {
+    /// Listen for both edges on every input.
+    ///
+    /// Register the GPIO interrupt handler (`Io::set_interrupt_handler`)
+    /// before storing this where the handler can reach it.
+    pub fn new(mut inputs: [Input<'d>; C]) -> Self {
+        let levels = core::array::from_fn(|i| inputs[i].is_high());
+        for input in inputs.iter_mut() {
+            input.listen(Event::AnyEdge);
+        }
+        Self { inputs, levels }
+    }
+
+    /// Last level recorded for input `index`
+    pub fn level(&self, index: usize) -> bool {
+        self.levels[index]
+    }
+
+    /// Handle a GPIO interrupt: queue the edges of all inputs that changed
+    pub fn on_interrupt<const N: usize>(&mut self, queue: &EdgeQueue<N>, now_ms: u32) {
+        for index in 0..C {
+            if self.inputs[index].is_interrupt_set() {
+                self.inputs[index].clear_interrupt();
+                self.record(index, queue, now_ms);
+            }
+        }
+    }
+
+    /// Arm light-sleep wake-up on the opposite of each input's recorded level.
+    ///
+    /// Returns `false` without arming anything while `queue` still holds
+    /// edges, which must be handled before sleeping. Call this in the same
+    /// critical section that reaches these inputs, so no edge can be queued
+    /// between the check and the arming; an input that changed since its last
+    /// recorded edge already has its wake level and wakes the chip at once.
+    ///
+    /// Once armed, the edge interrupts stop until [`EdgeInputs::resume`].
+    pub fn prepare_sleep<const N: usize>(
+        &mut self,
+        queue: &EdgeQueue<N>,
+    ) -> Result<bool, WakeConfigError> {
+        if !queue.is_empty() {
+            return Ok(false);
+        }
+        for (input, &high) in self.inputs.iter_mut().zip(&self.levels) {
+            let wake = if high {
+                WakeEvent::LowLevel
+            } else {
+                WakeEvent::HighLevel
+            };
+            input.wakeup_enable(true, wake)?;
+        }
+        Ok(true)
+    }
+
+    /// Disarm wake-up after light sleep, listen for edges again and queue any
+    /// level change that happened while asleep
+    pub fn resume<const N: usize>(
+        &mut self,
+        queue: &EdgeQueue<N>,
+        now_ms: u32,
+    ) -> Result<(), WakeConfigError> {
+        for index in 0..C {
+            let input = &mut self.inputs[index];
+            input.wakeup_enable(false, WakeEvent::HighLevel)?;
+            input.clear_interrupt();
+            input.listen(Event::AnyEdge);
+            self.record(index, queue, now_ms);
+        }
+        Ok(())
+    }
+
+    /// Stop listening and release the inputs
+    pub fn release(mut self) -> [Input<'d>; C] {
+        for input in self.inputs.iter_mut() {
+            input.unlisten();
+        }
+        self.inputs
+    }
+
+    /// Queue an edge if input `index` differs from its recorded level
+    fn record<const N: usize>(&mut self, index: usize, queue: &EdgeQueue<N>, now_ms: u32) {
+        let high = self.inputs[index].is_high();
+        if high != self.levels[index] {
+            self.levels[index] = high;
+            queue.push(Edge {
+                input: index,
+                high,
+                timestamp_ms: now_ms,
+            });
+        }
+    }
+}
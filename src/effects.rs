@@ -0,0 +1,318 @@
+//! Animated effects for RGB LED strips
+//!
+//! Each [`Effect`] renders a frame for a point in time into a slice of
+//! `RGB8` pixels, such as the 15 LEDs of the Grove RGB LED Stick:
+//!
+//! ```ignore
+//! let effect = Effect::Comet { color: RGB8::new(0, 64, 255), tail: 5, period_ms: 1500 };
+//! let mut frame = [RGB8::default(); 15];
+//! loop {
+//!     effect.render(now_ms(), &mut frame);
+//!     leds.write(frame.iter().cloned())?;
+//! }
+//! ```
+//!
+//! Rendering depends only on the timestamp, not on how many frames came
+//! before, so the animation speed does not change with the frame rate and a
+//! frame can be checked on the host for any time. Effects that look random
+//! (fire, twinkle) use a hash of the pixel and time instead of a random
+//! number generator for the same reason.
+
+use core::f32::consts::PI;
+
+use smart_leds::{
+    hsv::{hsv2rgb, Hsv},
+    RGB8,
+};
+
+/// An animated effect, repeating forever
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    /// All LEDs off
+    Off,
+    /// A rainbow across the strip, rotating once per `period_ms`
+    Rainbow { period_ms: u32, brightness: u8 },
+    /// Every third LED lit, moving one LED per `step_ms` like a marquee
+    TheaterChase { color: RGB8, step_ms: u32 },
+    /// A bright head with a fading `tail`, crossing the strip every `period_ms`
+    Comet {
+        color: RGB8,
+        tail: u8,
+        period_ms: u32,
+    },
+    /// Flickering flames rising from the first LED, changing every `step_ms`
+    Fire { step_ms: u32 },
+    /// LEDs fading in and out at random; `density` (0-255) sets how many
+    Twinkle {
+        color: RGB8,
+        density: u8,
+        period_ms: u32,
+    },
+    /// Fill the strip with `color` one LED per `step_ms`, then clear it again
+    ColorWipe { color: RGB8, step_ms: u32 },
+    /// All LEDs fading in and out with a full cycle every `period_ms`
+    Breathing { color: RGB8, period_ms: u32 },
+    /// A `width` LED wide eye sweeping back and forth every `period_ms`, as
+    /// on the Cylon and KITT
+    Larson {
+        color: RGB8,
+        width: u8,
+        period_ms: u32,
+    },
+}
+
+impl Effect {
+    /// Render the frame at `t_ms` since the effect started into `frame`
+    pub fn render(&self, t_ms: u32, frame: &mut [RGB8]) {
+        let len = frame.len();
+        if len == 0 {
+            return;
+        }
+
+        match *self {
+            Effect::Off => frame.fill(RGB8::default()),
+            Effect::Rainbow {
+                period_ms,
+                brightness,
+            } => {
+                let offset = phase(t_ms, period_ms) * 256.0;
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    *pixel = hsv2rgb(Hsv {
+                        hue: (offset as usize + i * 256 / len) as u8,
+                        sat: 255,
+                        val: brightness,
+                    });
+                }
+            }
+            Effect::TheaterChase { color, step_ms } => {
+                let step = (t_ms / step_ms.max(1)) as usize;
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    let lit = (i + 3 - step % 3).is_multiple_of(3);
+                    *pixel = if lit { color } else { RGB8::default() };
+                }
+            }
+            Effect::Comet {
+                color,
+                tail,
+                period_ms,
+            } => {
+                // The head runs past the end until the tail has left too
+                let tail = tail.max(1) as f32;
+                let head = phase(t_ms, period_ms) * (len as f32 + tail);
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    let behind = head - i as f32;
+                    let level = if (0.0..tail).contains(&behind) {
+                        1.0 - behind / tail
+                    } else {
+                        0.0
+                    };
+                    *pixel = scale(color, level);
+                }
+            }
+            Effect::Fire { step_ms } => {
+                let step_ms = step_ms.max(1);
+                let step = t_ms / step_ms;
+                let blend = (t_ms % step_ms) as f32 / step_ms as f32;
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    // Hotter at the base, with flicker blended between steps
+                    let from = noise(i as u32, step) as f32;
+                    let to = noise(i as u32, step.wrapping_add(1)) as f32;
+                    let flicker = (from + (to - from) * blend) / 255.0;
+                    let base = 1.0 - i as f32 / len as f32;
+                    *pixel = heat_color((220.0 * base * (0.3 + 0.7 * flicker)) as u8);
+                }
+            }
+            Effect::Twinkle {
+                color,
+                density,
+                period_ms,
+            } => {
+                let period_ms = period_ms.max(1);
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    // Each LED runs its own cycle, offset so they do not sync
+                    let offset = (noise(i as u32, u32::MAX) as u64 * period_ms as u64 / 256) as u32;
+                    let t = t_ms.wrapping_add(offset);
+                    let lit = noise(i as u32, t / period_ms) < density;
+                    let level = if lit {
+                        libm::sinf(PI * phase(t, period_ms))
+                    } else {
+                        0.0
+                    };
+                    *pixel = scale(color, level);
+                }
+            }
+            Effect::ColorWipe { color, step_ms } => {
+                let step = (t_ms / step_ms.max(1)) as usize % (2 * len);
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    let lit = if step < len {
+                        i <= step
+                    } else {
+                        i > step - len
+                    };
+                    *pixel = if lit { color } else { RGB8::default() };
+                }
+            }
+            Effect::Breathing { color, period_ms } => {
+                let level = 0.5 * (1.0 - libm::cosf(2.0 * PI * phase(t_ms, period_ms)));
+                frame.fill(scale(color, level));
+            }
+            Effect::Larson {
+                color,
+                width,
+                period_ms,
+            } => {
+                // Out and back once per period
+                let p = phase(t_ms, period_ms) * 2.0;
+                let eye = (len - 1) as f32 * if p < 1.0 { p } else { 2.0 - p };
+                let width = width.max(1) as f32;
+                for (i, pixel) in frame.iter_mut().enumerate() {
+                    let distance = libm::fabsf(i as f32 - eye);
+                    *pixel = scale(color, (1.0 - distance / width).max(0.0));
+                }
+            }
+        }
+    }
+}
+
+/// Scale `color` by `level` (0.0-1.0)
+pub fn scale(color: RGB8, level: f32) -> RGB8 {
+    let level = level.clamp(0.0, 1.0);
+    let channel = |c: u8| libm::roundf(c as f32 * level) as u8;
+    RGB8::new(channel(color.r), channel(color.g), channel(color.b))
+}
+
+/// Color of a flame at `heat` (0-255): black, red, yellow, then white
+pub fn heat_color(heat: u8) -> RGB8 {
+    // Three equal ramps, each brightening one more channel
+    let ramp = ((heat as u16 * 3) % 256) as u8;
+    match heat as u16 * 3 / 256 {
+        0 => RGB8::new(ramp, 0, 0),
+        1 => RGB8::new(255, ramp, 0),
+        _ => RGB8::new(255, 255, ramp),
+    }
+}
+
+/// Fraction (0.0-1.0) of the way through the current `period_ms`
+fn phase(t_ms: u32, period_ms: u32) -> f32 {
+    let period_ms = period_ms.max(1);
+    (t_ms % period_ms) as f32 / period_ms as f32
+}
+
+/// Pseudo-random byte for a pixel and time slot, always the same for the
+/// same inputs
+fn noise(pixel: u32, slot: u32) -> u8 {
+    let mut x = pixel.wrapping_mul(0x9e37_79b9) ^ slot.wrapping_mul(0x85eb_ca6b);
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x7feb_352d);
+    x ^= x >> 15;
+    x = x.wrapping_mul(0x846c_a68b);
+    x ^= x >> 16;
+    x as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OFF: RGB8 = RGB8::new(0, 0, 0);
+    const RED: RGB8 = RGB8::new(200, 0, 0);
+
+    fn frame<const N: usize>(effect: Effect, t_ms: u32) -> [RGB8; N] {
+        let mut frame = [RGB8::new(1, 2, 3); N];
+        effect.render(t_ms, &mut frame);
+        frame
+    }
+
+    #[test]
+    fn comet_head_and_tail() {
+        let comet = Effect::Comet {
+            color: RGB8::new(200, 100, 0),
+            tail: 4,
+            period_ms: 1400,
+        };
+        // Halfway through the period the head is at 0.5 * (10 + 4)
+        let frame = frame::<10>(comet, 700);
+        assert_eq!(frame[7], RGB8::new(200, 100, 0));
+        assert_eq!(frame[6], RGB8::new(150, 75, 0));
+        assert_eq!(frame[5], RGB8::new(100, 50, 0));
+        assert_eq!(frame[4], RGB8::new(50, 25, 0));
+        assert_eq!(frame[..4], [OFF; 4]);
+        assert_eq!(frame[8..], [OFF; 2]);
+    }
+
+    #[test]
+    fn color_wipe_fills_then_clears() {
+        let wipe = Effect::ColorWipe {
+            color: RED,
+            step_ms: 10,
+        };
+        assert_eq!(frame::<4>(wipe, 0), [RED, OFF, OFF, OFF]);
+        assert_eq!(frame::<4>(wipe, 39), [RED; 4]);
+        // At step == len the first LED is cleared again
+        assert_eq!(frame::<4>(wipe, 40), [OFF, RED, RED, RED]);
+        assert_eq!(frame::<4>(wipe, 70), [OFF; 4]);
+        assert_eq!(frame::<4>(wipe, 80), [RED, OFF, OFF, OFF]);
+    }
+
+    #[test]
+    fn theater_chase_phase() {
+        let chase = Effect::TheaterChase {
+            color: RED,
+            step_ms: 100,
+        };
+        assert_eq!(frame::<6>(chase, 0), [RED, OFF, OFF, RED, OFF, OFF]);
+        assert_eq!(frame::<6>(chase, 100), [OFF, RED, OFF, OFF, RED, OFF]);
+        assert_eq!(frame::<6>(chase, 250), [OFF, OFF, RED, OFF, OFF, RED]);
+        assert_eq!(frame::<6>(chase, 300), frame::<6>(chase, 0));
+    }
+
+    #[test]
+    fn larson_turns_around_at_the_last_led() {
+        let larson = Effect::Larson {
+            color: RED,
+            width: 1,
+            period_ms: 1000,
+        };
+        assert_eq!(frame::<5>(larson, 0), [RED, OFF, OFF, OFF, OFF]);
+        assert_eq!(frame::<5>(larson, 500), [OFF, OFF, OFF, OFF, RED]);
+        // Symmetric around the turn, never past the end
+        let before = frame::<5>(larson, 450);
+        assert_eq!(before, frame::<5>(larson, 550));
+        assert_eq!(before[3..], [RGB8::new(80, 0, 0), RGB8::new(120, 0, 0)]);
+        assert_eq!(frame::<5>(larson, 1000), frame::<5>(larson, 0));
+    }
+
+    #[test]
+    fn fire_and_twinkle_are_deterministic() {
+        let effects = [
+            Effect::Fire { step_ms: 50 },
+            Effect::Twinkle {
+                color: RED,
+                density: 128,
+                period_ms: 400,
+            },
+        ];
+        for effect in effects {
+            for t_ms in [0, 1234, 98_765, u32::MAX] {
+                let first = frame::<15>(effect, t_ms);
+                // Rendering another time in between does not change it
+                frame::<15>(effect, t_ms.wrapping_add(17));
+                assert_eq!(frame::<15>(effect, t_ms), first, "{effect:?} at {t_ms}");
+            }
+            assert_ne!(frame::<15>(effect, 0), frame::<15>(effect, 1234));
+        }
+    }
+
+    #[test]
+    fn twinkle_with_a_long_period() {
+        // Offsets up to 255/256 of the period must not overflow
+        let twinkle = Effect::Twinkle {
+            color: RED,
+            density: 255,
+            period_ms: u32::MAX / 2,
+        };
+        let first = frame::<15>(twinkle, u32::MAX);
+        assert_eq!(first, frame::<15>(twinkle, u32::MAX));
+        assert!(first.iter().any(|&pixel| pixel != OFF));
+    }
+}
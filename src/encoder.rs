@@ -0,0 +1,288 @@
+//! Quadrature rotary encoder decoder
+//!
+//! [`Encoder`] decodes the A/B signals of a rotary encoder with a state
+//! table: every valid transition between the four A/B states is one step
+//! clockwise or counter-clockwise, and invalid transitions (contact bounce,
+//! missed samples) are ignored. Steps are grouped into detents (the clicks
+//! felt when turning, usually four steps each), and quick turns can be
+//! accelerated so large values are reachable with a few flicks.
+//!
+//! The decoder only needs the two levels and a timestamp, so it can be fed
+//! from a polling loop through [`RotaryEncoder`] or from interrupt-driven
+//! [`edge`](crate::edge) events.
+
+use embedded_hal::digital::InputPin;
+
+use crate::button::{self, Button};
+
+/// Step for a transition, indexed by `previous << 2 | current` where a
+/// state is `a << 1 | b`
+#[rustfmt::skip]
+const TRANSITIONS: [i8; 16] = [
+     0, -1,  1,  0,
+     1,  0,  0, -1,
+    -1,  0,  0,  1,
+     0,  1, -1,  0,
+];
+
+/// Decoder state for one encoder
+#[derive(Clone, Debug)]
+pub struct Encoder {
+    state: u8,
+    steps: i8,
+    steps_per_detent: i8,
+    reversed: bool,
+    position: i32,
+    accel_ms: u32,
+    max_multiplier: i32,
+    last_detent_ms: Option<u32>,
+}
+
+impl Encoder {
+    /// Create a decoder starting from the current A and B levels
+    pub fn new(a: bool, b: bool) -> Self {
+        Self {
+            state: Self::state(a, b),
+            steps: 0,
+            steps_per_detent: 4,
+            reversed: false,
+            position: 0,
+            accel_ms: 0,
+            max_multiplier: 1,
+            last_detent_ms: None,
+        }
+    }
+
+    /// Set the number of steps per detent (default 4, use 1 for full resolution)
+    pub fn with_steps_per_detent(mut self, steps: u8) -> Self {
+        self.steps_per_detent = steps.clamp(1, 4) as i8;
+        self
+    }
+
+    /// Swap the direction, for encoders wired the other way round
+    pub fn with_reversed(mut self) -> Self {
+        self.reversed = true;
+        self
+    }
+
+    /// Accelerate turns faster than one detent per `accel_ms`.
+    ///
+    /// A detent `accel_ms / n` after the previous one counts `n` times, up to
+    /// `max_multiplier`.
+    pub fn with_acceleration(mut self, accel_ms: u32, max_multiplier: u8) -> Self {
+        self.accel_ms = accel_ms;
+        self.max_multiplier = max_multiplier.max(1) as i32;
+        self
+    }
+
+    /// Signed position in (accelerated) detents
+    pub fn position(&self) -> i32 {
+        self.position
+    }
+
+    /// Move the position, e.g. back to zero
+    pub fn set_position(&mut self, position: i32) {
+        self.position = position;
+    }
+
+    /// Feed the A and B levels at `now_ms`; returns the position change when
+    /// a detent was completed
+    pub fn update(&mut self, a: bool, b: bool, now_ms: u32) -> Option<i32> {
+        let state = Self::state(a, b);
+        let step = TRANSITIONS[((self.state << 2) | state) as usize];
+        self.state = state;
+
+        if step == 0 {
+            return None;
+        }
+
+        self.steps += if self.reversed { -step } else { step };
+        if self.steps.abs() < self.steps_per_detent {
+            return None;
+        }
+
+        let direction = self.steps.signum() as i32;
+        self.steps = 0;
+
+        let delta = direction * self.multiplier(now_ms);
+        self.position = self.position.wrapping_add(delta);
+        Some(delta)
+    }
+
+    fn multiplier(&mut self, now_ms: u32) -> i32 {
+        let previous = self.last_detent_ms.replace(now_ms);
+        match previous {
+            Some(previous) if self.accel_ms > 0 => {
+                let interval = now_ms.wrapping_sub(previous).max(1);
+                ((self.accel_ms / interval) as i32).clamp(1, self.max_multiplier)
+            }
+            _ => 1,
+        }
+    }
+
+    fn state(a: bool, b: bool) -> u8 {
+        ((a as u8) << 1) | b as u8
+    }
+}
+
+/// Something that happened to a [`RotaryEncoder`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotaryEvent {
+    /// The knob was turned by `delta` detents to `position`
+    Turned { delta: i32, position: i32 },
+    /// An event of the push button
+    Button(button::Event),
+}
+
+/// A rotary encoder on two input pins, with an optional push button
+pub struct RotaryEncoder<P> {
+    a: P,
+    b: P,
+    encoder: Encoder,
+    button: Option<Button<P>>,
+}
+
+impl<P: InputPin> RotaryEncoder<P> {
+    /// Create an encoder on pins `a` and `b`, with the default decoder
+    pub fn new(mut a: P, mut b: P) -> Result<Self, P::Error> {
+        let encoder = Encoder::new(a.is_high()?, b.is_high()?);
+        Ok(Self {
+            a,
+            b,
+            encoder,
+            button: None,
+        })
+    }
+
+    /// Configure the decoder (detents, direction, acceleration)
+    pub fn with_encoder(mut self, configure: impl FnOnce(Encoder) -> Encoder) -> Self {
+        self.encoder = configure(self.encoder);
+        self
+    }
+
+    /// Add the encoder's push button
+    pub fn with_button(mut self, button: Button<P>) -> Self {
+        self.button = Some(button);
+        self
+    }
+
+    /// Signed position in detents
+    pub fn position(&self) -> i32 {
+        self.encoder.position()
+    }
+
+    /// Move the position, e.g. back to zero
+    pub fn set_position(&mut self, position: i32) {
+        self.encoder.set_position(position);
+    }
+
+    /// Read the pins at `now_ms` and return the next event.
+    ///
+    /// Poll this often (every millisecond or so); fast turns can be missed
+    /// otherwise. Use [`edge`](crate::edge) interrupts for reliable decoding.
+    pub fn update(&mut self, now_ms: u32) -> Result<Option<RotaryEvent>, P::Error> {
+        let (a, b) = (self.a.is_high()?, self.b.is_high()?);
+        if let Some(delta) = self.encoder.update(a, b, now_ms) {
+            return Ok(Some(RotaryEvent::Turned {
+                delta,
+                position: self.encoder.position(),
+            }));
+        }
+
+        match self.button.as_mut() {
+            Some(button) => Ok(button.update(now_ms)?.map(RotaryEvent::Button)),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A/B levels of one clockwise detent, starting and ending at rest
+    const CLOCKWISE: [(bool, bool); 4] =
+        [(true, false), (true, true), (false, true), (false, false)];
+
+    fn turn(encoder: &mut Encoder, states: &[(bool, bool)], now_ms: u32) -> Vec<Option<i32>> {
+        states
+            .iter()
+            .map(|&(a, b)| encoder.update(a, b, now_ms))
+            .collect()
+    }
+
+    #[test]
+    fn transition_table_is_antisymmetric() {
+        for previous in 0..4 {
+            for current in 0..4 {
+                let forward = TRANSITIONS[previous << 2 | current];
+                let backward = TRANSITIONS[current << 2 | previous];
+                assert_eq!(forward, -backward);
+                // Staying put and jumping both signals at once are no steps
+                if previous == current || previous ^ current == 0b11 {
+                    assert_eq!(forward, 0);
+                } else {
+                    assert_ne!(forward, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn one_detent_per_four_steps() {
+        let mut encoder = Encoder::new(false, false);
+        assert_eq!(
+            turn(&mut encoder, &CLOCKWISE, 0),
+            [None, None, None, Some(1)]
+        );
+
+        let mut counter_clockwise = CLOCKWISE;
+        counter_clockwise.reverse();
+        counter_clockwise.rotate_left(1);
+        assert_eq!(
+            turn(&mut encoder, &counter_clockwise, 0),
+            [None, None, None, Some(-1)]
+        );
+        assert_eq!(encoder.position(), 0);
+    }
+
+    #[test]
+    fn bounces_and_invalid_transitions_are_ignored() {
+        let mut encoder = Encoder::new(false, false);
+        let bounce = [(true, false), (false, false), (true, false), (false, false)];
+        assert_eq!(turn(&mut encoder, &bounce, 0), [None; 4]);
+        // Both signals changing at once is a missed sample: no step either way
+        let jump = [(true, true), (false, false)];
+        assert_eq!(turn(&mut encoder, &jump, 0), [None; 2]);
+        assert_eq!(
+            turn(&mut encoder, &CLOCKWISE, 0),
+            [None, None, None, Some(1)]
+        );
+    }
+
+    #[test]
+    fn full_resolution_and_reversed() {
+        let mut encoder = Encoder::new(false, false)
+            .with_steps_per_detent(1)
+            .with_reversed();
+        assert_eq!(turn(&mut encoder, &CLOCKWISE, 0), [Some(-1); 4]);
+        assert_eq!(encoder.position(), -4);
+        encoder.set_position(0);
+        assert_eq!(encoder.position(), 0);
+    }
+
+    #[test]
+    fn acceleration() {
+        let mut encoder = Encoder::new(false, false)
+            .with_steps_per_detent(1)
+            .with_acceleration(100, 4);
+        let states = CLOCKWISE.iter().cycle();
+        let times = [0, 50, 60, 1000, 1100];
+        let deltas: Vec<_> = states
+            .zip(times)
+            .map(|(&(a, b), now)| encoder.update(a, b, now))
+            .collect();
+        assert_eq!(deltas, [Some(1), Some(2), Some(4), Some(1), Some(1)]);
+        assert_eq!(encoder.position(), 9);
+    }
+}
@@ -0,0 +1,201 @@
+//! Radix-2 FFT for ADC sample blocks
+//!
+//! A small in-place, iterative Cooley-Tukey FFT over `f32`. Twiddle factors
+//! and the window function are computed once in [`Fft::new`], so transforming
+//! a block only costs the butterflies. `N` must be a power of two; 128 or 256
+//! points are a good fit for the sound sensor blocks produced by
+//! [`crate::sampler`].
+
+use core::f32::consts::PI;
+
+/// Window function applied to a block before transforming it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Window {
+    /// No windowing (best resolution, worst leakage)
+    Rectangular,
+    /// Hann window (good general purpose choice)
+    Hann,
+    /// Hamming window
+    Hamming,
+    /// Blackman window (lowest leakage, widest main lobe)
+    Blackman,
+}
+
+impl Window {
+    /// Coefficient of the window for sample `i` of an `n` sample block
+    pub fn coefficient(self, i: usize, n: usize) -> f32 {
+        let x = 2.0 * PI * i as f32 / (n - 1) as f32;
+        match self {
+            Window::Rectangular => 1.0,
+            Window::Hann => 0.5 - 0.5 * libm::cosf(x),
+            Window::Hamming => 0.54 - 0.46 * libm::cosf(x),
+            Window::Blackman => 0.42 - 0.5 * libm::cosf(x) + 0.08 * libm::cosf(2.0 * x),
+        }
+    }
+}
+
+/// Precomputed `N` point FFT
+pub struct Fft<const N: usize> {
+    window: [f32; N],
+    // Sum of the window coefficients, used to normalize magnitudes
+    window_gain: f32,
+    // cos of -2*pi*k/N for k in 0..N/2, followed by the sines at N/2 + k
+    twiddles: [f32; N],
+}
+
+impl<const N: usize> Fft<N> {
+    /// Precompute twiddle factors and window coefficients.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is not a power of two or smaller than 2.
+    pub fn new(window: Window) -> Self {
+        assert!(
+            N >= 2 && N.is_power_of_two(),
+            "FFT size must be a power of two"
+        );
+
+        let mut fft = Self {
+            window: [0.0; N],
+            window_gain: 0.0,
+            twiddles: [0.0; N],
+        };
+
+        for i in 0..N {
+            let w = window.coefficient(i, N);
+            fft.window[i] = w;
+            fft.window_gain += w;
+        }
+
+        for k in 0..N / 2 {
+            let angle = -2.0 * PI * k as f32 / N as f32;
+            fft.twiddles[k] = libm::cosf(angle);
+            fft.twiddles[N / 2 + k] = libm::sinf(angle);
+        }
+
+        fft
+    }
+
+    /// In-place complex forward transform of `re` + j`im`
+    pub fn transform(&self, re: &mut [f32; N], im: &mut [f32; N]) {
+        // Bit-reversal permutation
+        let bits = N.trailing_zeros();
+        for i in 0..N {
+            let j = i.reverse_bits() >> (usize::BITS - bits);
+            if j > i {
+                re.swap(i, j);
+                im.swap(i, j);
+            }
+        }
+
+        // Butterflies
+        let (cos, sin) = self.twiddles.split_at(N / 2);
+        let mut size = 2;
+        while size <= N {
+            let half = size / 2;
+            let step = N / size;
+            for start in (0..N).step_by(size) {
+                for k in 0..half {
+                    let (wr, wi) = (cos[k * step], sin[k * step]);
+                    let a = start + k;
+                    let b = a + half;
+                    let tr = re[b] * wr - im[b] * wi;
+                    let ti = re[b] * wi + im[b] * wr;
+                    re[b] = re[a] - tr;
+                    im[b] = im[a] - ti;
+                    re[a] += tr;
+                    im[a] += ti;
+                }
+            }
+            size *= 2;
+        }
+    }
+
+    /// Compute the single-sided amplitude spectrum of a block of ADC samples.
+    ///
+    /// The DC offset is removed and the window applied before transforming.
+    /// `out[k]` receives the amplitude of bin `k` in the input unit (e.g. mV)
+    /// for `k < min(out.len(), N / 2)`.
+    pub fn magnitudes(&self, samples: &[u16; N], out: &mut [f32]) {
+        let mean = samples.iter().map(|&s| s as f32).sum::<f32>() / N as f32;
+
+        let mut re = [0.0f32; N];
+        let mut im = [0.0f32; N];
+        for (i, &s) in samples.iter().enumerate() {
+            re[i] = (s as f32 - mean) * self.window[i];
+        }
+
+        self.transform(&mut re, &mut im);
+
+        let scale = 2.0 / self.window_gain;
+        for (k, out) in out.iter_mut().take(N / 2).enumerate() {
+            *out = libm::sqrtf(re[k] * re[k] + im[k] * im[k]) * scale;
+        }
+    }
+
+    /// Center frequency of bin `k` in Hz for the given sample rate
+    pub fn bin_frequency(bin: usize, sample_rate_hz: u32) -> f32 {
+        bin as f32 * sample_rate_hz as f32 / N as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const N: usize = 64;
+    const SAMPLE_RATE_HZ: u32 = 6400;
+
+    /// A sine of `amplitude` at `frequency_hz` around a mid-scale offset
+    fn sine(frequency_hz: f32, amplitude: f32) -> [u16; N] {
+        core::array::from_fn(|i| {
+            let t = i as f32 / SAMPLE_RATE_HZ as f32;
+            libm::roundf(2000.0 + amplitude * libm::sinf(2.0 * PI * frequency_hz * t)) as u16
+        })
+    }
+
+    fn peak(bins: &[f32]) -> usize {
+        (0..bins.len())
+            .max_by(|&a, &b| bins[a].total_cmp(&bins[b]))
+            .unwrap()
+    }
+
+    #[test]
+    fn impulse_has_a_flat_spectrum() {
+        let fft = Fft::<8>::new(Window::Rectangular);
+        let mut re = [0.0; 8];
+        let mut im = [0.0; 8];
+        re[0] = 1.0;
+        fft.transform(&mut re, &mut im);
+        assert_eq!(re, [1.0; 8]);
+        assert_eq!(im, [0.0; 8]);
+    }
+
+    #[test]
+    fn sine_lands_in_its_bin() {
+        assert_eq!(Fft::<N>::bin_frequency(8, SAMPLE_RATE_HZ), 800.0);
+
+        let fft = Fft::<N>::new(Window::Rectangular);
+        let mut bins = [0.0; N / 2];
+        fft.magnitudes(&sine(800.0, 1000.0), &mut bins);
+        assert_eq!(peak(&bins), 8);
+        assert!((bins[8] - 1000.0).abs() < 5.0, "{}", bins[8]);
+        for (k, &amplitude) in bins.iter().enumerate() {
+            if k != 8 {
+                assert!(amplitude < 5.0, "bin {k}: {amplitude}");
+            }
+        }
+    }
+
+    #[test]
+    fn windowed_amplitude_is_normalized() {
+        for window in [Window::Hann, Window::Hamming, Window::Blackman] {
+            let fft = Fft::<N>::new(window);
+            let mut bins = [0.0; N / 2];
+            fft.magnitudes(&sine(1500.0, 500.0), &mut bins);
+            assert_eq!(peak(&bins), 15, "{window:?}");
+            assert!((bins[15] - 500.0).abs() < 25.0, "{window:?}: {}", bins[15]);
+            assert!(bins[5] < 5.0 && bins[25] < 5.0, "{window:?}");
+        }
+    }
+}
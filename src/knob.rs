@@ -0,0 +1,189 @@
+//! Analog knob (potentiometer) input
+//!
+//! Raw potentiometer readings jitter by a few counts even when the knob is
+//! not touched, never quite reach the rail voltages and are linear, while
+//! things like volume feel better with an audio taper. [`Knob`] cleans a
+//! stream of readings up in stages:
+//!
+//! 1. median of the last `M` readings to drop single spikes,
+//! 2. exponential moving average to smooth the remaining noise,
+//! 3. endpoint calibration to a 0.0-1.0 position,
+//! 4. hysteresis deadband so the position only changes on real movement,
+//! 5. optional logarithmic taper emulation.
+//!
+//! The resulting position can be mapped onto any numeric range with
+//! [`Knob::map`] or the free-standing [`map_range`].
+
+/// Types that can be linearly interpolated between two values
+pub trait Lerp: Copy {
+    /// Value at fraction `t` (0.0-1.0) between `start` and `end`
+    fn lerp(start: Self, end: Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(start: Self, end: Self, t: f32) -> Self {
+        start + (end - start) * t
+    }
+}
+
+macro_rules! impl_lerp_int {
+    ($($ty:ty),*) => {
+        $(
+            impl Lerp for $ty {
+                fn lerp(start: Self, end: Self, t: f32) -> Self {
+                    let value = start as f32 + (end as f32 - start as f32) * t;
+                    libm::roundf(value) as $ty
+                }
+            }
+        )*
+    };
+}
+
+impl_lerp_int!(u8, u16, u32, usize, i8, i16, i32, isize);
+
+/// Map `value` from `in_min..=in_max` onto `out_min..=out_max`.
+///
+/// The input is clamped to its range, so the output never leaves the target
+/// range. Either range may be descending.
+pub fn map_range<T: Lerp>(value: f32, in_min: f32, in_max: f32, out_min: T, out_max: T) -> T {
+    let span = in_max - in_min;
+    let t = if span == 0.0 {
+        0.0
+    } else {
+        ((value - in_min) / span).clamp(0.0, 1.0)
+    };
+    T::lerp(out_min, out_max, t)
+}
+
+/// Response curve applied to the knob position
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Taper {
+    /// Output follows the rotation
+    Linear,
+    /// Audio taper: 10% output at half rotation, fine control at the low end
+    Logarithmic,
+    /// Reverse audio taper: 90% output at half rotation
+    Antilogarithmic,
+}
+
+impl Taper {
+    // Base giving 10% output at 50% rotation: 1 / (sqrt(b) + 1) = 0.1
+    const BASE: f32 = 81.0;
+
+    /// Apply the curve to a linear position (0.0-1.0)
+    pub fn apply(self, x: f32) -> f32 {
+        let x = x.clamp(0.0, 1.0);
+        match self {
+            Taper::Linear => x,
+            Taper::Logarithmic => (libm::powf(Self::BASE, x) - 1.0) / (Self::BASE - 1.0),
+            Taper::Antilogarithmic => {
+                1.0 - (libm::powf(Self::BASE, 1.0 - x) - 1.0) / (Self::BASE - 1.0)
+            }
+        }
+    }
+}
+
+/// Filtered, calibrated knob position from a stream of readings.
+///
+/// `M` is the median window length; 1 disables median filtering.
+pub struct Knob<const M: usize> {
+    history: [u16; M],
+    filled: usize,
+    next: usize,
+    alpha: f32,
+    smoothed: Option<f32>,
+    min: u16,
+    max: u16,
+    deadband: f32,
+    position: f32,
+    taper: Taper,
+}
+
+impl<const M: usize> Knob<M> {
+    /// Create a knob whose readings span `min..=max` (e.g. millivolts)
+    pub fn new(min: u16, max: u16) -> Self {
+        Self {
+            history: [0; M],
+            filled: 0,
+            next: 0,
+            alpha: 0.3,
+            smoothed: None,
+            min,
+            max,
+            deadband: 0.01,
+            position: 0.0,
+            taper: Taper::Linear,
+        }
+    }
+
+    /// Set the EMA weight of new readings (0.0-1.0, 1.0 disables smoothing)
+    pub fn with_smoothing(mut self, alpha: f32) -> Self {
+        self.alpha = alpha.clamp(0.01, 1.0);
+        self
+    }
+
+    /// Set the hysteresis deadband as a fraction of full scale
+    pub fn with_deadband(mut self, deadband: f32) -> Self {
+        self.deadband = deadband.max(0.0);
+        self
+    }
+
+    /// Set the response curve
+    pub fn with_taper(mut self, taper: Taper) -> Self {
+        self.taper = taper;
+        self
+    }
+
+    /// Change the readings that correspond to the end stops
+    pub fn set_calibration(&mut self, min: u16, max: u16) {
+        self.min = min;
+        self.max = max;
+    }
+
+    /// Feed a new reading. Returns `true` if the position changed.
+    pub fn update(&mut self, reading: u16) -> bool {
+        let median = self.median(reading) as f32;
+
+        let smoothed = match self.smoothed {
+            Some(previous) => previous + self.alpha * (median - previous),
+            None => median,
+        };
+        self.smoothed = Some(smoothed);
+
+        let linear = map_range(smoothed, self.min as f32, self.max as f32, 0.0, 1.0);
+
+        // Always let the end stops through so the full range stays reachable
+        let at_end = (linear <= 0.0 || linear >= 1.0) && linear != self.position;
+        if at_end || libm::fabsf(linear - self.position) > self.deadband {
+            self.position = linear;
+            return true;
+        }
+        false
+    }
+
+    /// Current position after taper, 0.0-1.0
+    pub fn position(&self) -> f32 {
+        self.taper.apply(self.position)
+    }
+
+    /// Current position mapped onto `start..=end`
+    pub fn map<T: Lerp>(&self, start: T, end: T) -> T {
+        T::lerp(start, end, self.position())
+    }
+
+    fn median(&mut self, reading: u16) -> u16 {
+        if M <= 1 {
+            return reading;
+        }
+
+        self.history[self.next] = reading;
+        self.next = (self.next + 1) % M;
+        self.filled = (self.filled + 1).min(M);
+
+        let mut window = [0u16; M];
+        window[..self.filled].copy_from_slice(&self.history[..self.filled]);
+        let window = &mut window[..self.filled];
+        window.sort_unstable();
+        window[self.filled / 2]
+    }
+}
@@ -0,0 +1,41 @@
+//! Reusable building blocks for the XIAO ESP32-C6 Grove examples.
+//!
+//! The examples in `examples/` pull shared functionality from this crate
+//! instead of duplicating it in every file.
+//!
+//! Modules driving esp-hal peripherals are only built for the chip. The rest
+//! is plain `no_std` code that also builds on the host, where its unit tests
+//! run with `cargo test-host`.
+
+#![cfg_attr(not(test), no_std)]
+
+#[cfg(target_os = "none")]
+pub mod adc;
+pub mod button;
+#[cfg(target_os = "none")]
+pub mod buzzer;
+pub mod color;
+pub mod dimmer;
+#[cfg(target_os = "none")]
+pub mod edge;
+pub mod effects;
+pub mod encoder;
+pub mod fft;
+pub mod knob;
+pub mod meter;
+pub mod morse;
+pub mod notes;
+pub mod panic;
+pub mod power;
+pub mod queue;
+pub mod rtttl;
+#[cfg(target_os = "none")]
+pub mod sampler;
+pub mod segments;
+pub mod sequencer;
+pub mod spectrum;
+pub mod status;
+pub mod strip;
+pub mod tracker;
+#[cfg(target_os = "none")]
+pub mod ws2812;
@@ -3,6 +3,10 @@
 //! The following wiring is assumed:
 //! - LED => GPIO15
 //!   https://github.com/espressif/arduino-esp32/blob/d47771f2cc649c3cd52a3f6eb3d9b97c82005ffb/variants/XIAO_ESP32C6/pins_arduino.h#L13
+//!
+//! The onboard LED is driven by the status LED pattern engine and shows each
+//! of its patterns for a few seconds: heartbeat, breathing, a 3-blink error
+//! code, fast error blink and SOS.
 
 #![no_std]
 #![no_main]
@@ -13,24 +17,42 @@ use esp_hal::{
     gpio::OutputConfig,
     gpio::{Level, Output},
     main,
-    time::Duration,
+    time::Instant,
+};
+use xiao_esp32c6_blinky::{
+    morse::ActiveLow,
+    status::{Pattern, StatusLed},
 };
 
 esp_bootloader_esp_idf::esp_app_desc!();
 
+const PATTERNS: [Pattern; 5] = [
+    Pattern::Heartbeat,
+    Pattern::Breathing { period_ms: 2000 },
+    Pattern::Code(3),
+    Pattern::ErrorBlink,
+    Pattern::Sos,
+];
+
+// How long each pattern is shown
+const PATTERN_MS: u32 = 8000;
+
 #[main]
 fn main() -> ! {
     let peripherals = esp_hal::init(esp_hal::Config::default());
 
-    // Set GPIO0 as an output, and set its state high initially.
-    let mut led = Output::new(peripherals.GPIO15, Level::High, OutputConfig::default());
+    // Set GPIO15 as an output, initially high (the LED lights when driven low)
+    let led = Output::new(peripherals.GPIO15, Level::High, OutputConfig::default());
+    let mut status = StatusLed::new(ActiveLow(led));
     let delay = Delay::new();
 
     loop {
-        led.toggle();
-        delay.delay_millis(250);
-        led.toggle();
-        // or using  duration
-        delay.delay(Duration::from_secs(1));
+        let now = Instant::now().duration_since_epoch().as_millis() as u32;
+
+        status.set_pattern(PATTERNS[(now / PATTERN_MS) as usize % PATTERNS.len()]);
+        status.update(now).unwrap();
+
+        // Update often enough for the dimmed levels of the breathing pattern
+        delay.delay_millis(1);
     }
 }
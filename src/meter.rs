@@ -0,0 +1,162 @@
+//! Bar graph (VU meter) on an RGB LED strip
+//!
+//! [`BarGraph`] shows any scalar reading (sound level, light, tilt angle,
+//! temperature...) as a bar on the LEDs of the Grove RGB LED Stick, without a
+//! display. The bar is colored by position along a gradient, follows rising
+//! readings at once and falls back slowly, and a peak-hold dot marks the
+//! recent maximum like on a hi-fi level meter:
+//!
+//! ```ignore
+//! let mut meter = BarGraph::new(0.0, 1500.0).with_peak_hold(1000, 0.5);
+//! loop {
+//!     meter.update(read_level_mv(), now_ms());
+//!     meter.render(&mut frame);
+//!     leds.write(frame.iter().cloned())?;
+//! }
+//! ```
+
+use smart_leds::RGB8;
+
+use crate::{effects::scale, knob::map_range};
+
+/// Green, yellow, red: the classic level meter colors
+pub const TRAFFIC_LIGHT: [RGB8; 3] = [
+    RGB8::new(0, 160, 0),
+    RGB8::new(160, 120, 0),
+    RGB8::new(160, 0, 0),
+];
+
+/// Color at `t` (0.0-1.0) along evenly spaced `stops`, blended in between
+pub fn gradient(stops: &[RGB8], t: f32) -> RGB8 {
+    match stops {
+        [] => RGB8::default(),
+        [color] => *color,
+        _ => {
+            let position = t.clamp(0.0, 1.0) * (stops.len() - 1) as f32;
+            let index = (position as usize).min(stops.len() - 2);
+            let blend = position - index as f32;
+            let (from, to) = (stops[index], stops[index + 1]);
+            let channel =
+                |a: u8, b: u8| libm::roundf(a as f32 + (b as f32 - a as f32) * blend) as u8;
+            RGB8::new(
+                channel(from.r, to.r),
+                channel(from.g, to.g),
+                channel(from.b, to.b),
+            )
+        }
+    }
+}
+
+/// A level meter mapping readings onto a LED strip
+pub struct BarGraph<'g> {
+    min: f32,
+    max: f32,
+    gradient: &'g [RGB8],
+    /// Fall rate of the bar in full scales per second
+    decay: f32,
+    /// How long the peak dot stays before falling; 0 hides it
+    peak_hold_ms: u32,
+    /// Fall rate of the peak dot in full scales per second
+    peak_decay: f32,
+    peak_color: RGB8,
+    level: f32,
+    peak: f32,
+    peak_ms: u32,
+    last_ms: Option<u32>,
+}
+
+impl<'g> BarGraph<'g> {
+    /// Show readings from `min` (empty) to `max` (full); `min` may be greater
+    /// than `max` to fill the bar as the reading drops
+    pub fn new(min: f32, max: f32) -> Self {
+        Self {
+            min,
+            max,
+            gradient: &TRAFFIC_LIGHT,
+            decay: 1.5,
+            peak_hold_ms: 0,
+            peak_decay: 0.5,
+            peak_color: RGB8::new(160, 160, 160),
+            level: 0.0,
+            peak: 0.0,
+            peak_ms: 0,
+            last_ms: None,
+        }
+    }
+
+    /// Color the bar along `gradient` from the first to the last LED
+    pub fn with_gradient(mut self, gradient: &'g [RGB8]) -> Self {
+        self.gradient = gradient;
+        self
+    }
+
+    /// Set how fast the bar falls, in full scales per second; 0 disables the
+    /// decay so the bar follows the reading exactly
+    pub fn with_decay(mut self, per_second: f32) -> Self {
+        self.decay = per_second;
+        self
+    }
+
+    /// Show a peak dot that stays for `hold_ms` and then falls at
+    /// `per_second` full scales per second
+    pub fn with_peak_hold(mut self, hold_ms: u32, per_second: f32) -> Self {
+        self.peak_hold_ms = hold_ms;
+        self.peak_decay = per_second;
+        self
+    }
+
+    /// Set the color of the peak dot
+    pub fn with_peak_color(mut self, color: RGB8) -> Self {
+        self.peak_color = color;
+        self
+    }
+
+    /// Displayed level (0.0-1.0)
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+
+    /// Peak level (0.0-1.0)
+    pub fn peak(&self) -> f32 {
+        self.peak
+    }
+
+    /// Feed a reading taken at `now_ms`
+    pub fn update(&mut self, value: f32, now_ms: u32) {
+        let target = map_range(value, self.min, self.max, 0.0, 1.0);
+        let elapsed = self.last_ms.map_or(0, |last| now_ms.wrapping_sub(last)) as f32 / 1000.0;
+        self.last_ms = Some(now_ms);
+
+        // Rise at once, fall at the decay rate
+        self.level = if self.decay > 0.0 {
+            target.max(self.level - self.decay * elapsed)
+        } else {
+            target
+        };
+
+        if self.level >= self.peak {
+            self.peak = self.level;
+            self.peak_ms = now_ms;
+        } else if now_ms.wrapping_sub(self.peak_ms) >= self.peak_hold_ms {
+            self.peak = (self.peak - self.peak_decay * elapsed).max(self.level);
+        }
+    }
+
+    /// Draw the bar into `frame`, from the first LED up
+    pub fn render(&self, frame: &mut [RGB8]) {
+        let count = frame.len();
+        let lit = self.level * count as f32;
+        let last = count.saturating_sub(1).max(1) as f32;
+
+        for (i, pixel) in frame.iter_mut().enumerate() {
+            // The top LED of the bar is partly lit for in-between levels
+            let fill = (lit - i as f32).clamp(0.0, 1.0);
+            *pixel = scale(gradient(self.gradient, i as f32 / last), fill);
+        }
+
+        if self.peak_hold_ms > 0 && self.peak > self.level && count > 0 {
+            let index = (libm::ceilf(self.peak * count as f32) as usize).clamp(1, count) - 1;
+            frame[index] = self.peak_color;
+        }
+    }
+}
@@ -0,0 +1,576 @@
+//! Morse code encoder and decoder
+//!
+//! [`Encoder`] turns ASCII text into a sequence of on/off [`Event`]s using
+//! standard Morse timing: a dah is three dits, elements of a character are
+//! separated by one dit, characters by three and words by seven. The dit
+//! length follows from the speed in words per minute ("PARIS" standard).
+//!
+//! With Farnsworth timing the characters themselves are sent at a higher
+//! speed while the gaps between them are stretched to reach a lower overall
+//! speed, which makes it easier to learn to recognize characters by sound.
+//!
+//! Events can be sent to anything implementing [`Keyer`]: a GPIO LED (any
+//! `embedded_hal` `OutputPin`, see [`ActiveLow`] for the onboard LED) or the
+//! Grove Buzzer via [`BuzzerKeyer`].
+//!
+//! [`Decoder`] does the reverse for a hand-keyed input such as a button: it
+//! measures key-down and key-up times, adapts its dit length to the sender's
+//! speed and turns the elements back into characters.
+
+use core::{iter::Peekable, str::Chars};
+
+use embedded_hal::{delay::DelayNs, digital::OutputPin};
+
+#[cfg(target_os = "none")]
+use crate::buzzer::{self, Buzzer};
+
+/// Morse pattern of `c` as dots and dashes, or `None` if it has no code
+pub fn code(c: char) -> Option<&'static str> {
+    let code = match c.to_ascii_uppercase() {
+        'A' => ".-",
+        'B' => "-...",
+        'C' => "-.-.",
+        'D' => "-..",
+        'E' => ".",
+        'F' => "..-.",
+        'G' => "--.",
+        'H' => "....",
+        'I' => "..",
+        'J' => ".---",
+        'K' => "-.-",
+        'L' => ".-..",
+        'M' => "--",
+        'N' => "-.",
+        'O' => "---",
+        'P' => ".--.",
+        'Q' => "--.-",
+        'R' => ".-.",
+        'S' => "...",
+        'T' => "-",
+        'U' => "..-",
+        'V' => "...-",
+        'W' => ".--",
+        'X' => "-..-",
+        'Y' => "-.--",
+        'Z' => "--..",
+        '0' => "-----",
+        '1' => ".----",
+        '2' => "..---",
+        '3' => "...--",
+        '4' => "....-",
+        '5' => ".....",
+        '6' => "-....",
+        '7' => "--...",
+        '8' => "---..",
+        '9' => "----.",
+        '.' => ".-.-.-",
+        ',' => "--..--",
+        '?' => "..--..",
+        '\'' => ".----.",
+        '!' => "-.-.--",
+        '/' => "-..-.",
+        '(' => "-.--.",
+        ')' => "-.--.-",
+        '&' => ".-...",
+        ':' => "---...",
+        ';' => "-.-.-.",
+        '=' => "-...-",
+        '+' => ".-.-.",
+        '-' => "-....-",
+        '_' => "..--.-",
+        '"' => ".-..-.",
+        '$' => "...-..-",
+        '@' => ".--.-.",
+        _ => return None,
+    };
+    Some(code)
+}
+
+/// Characters that have a Morse code, used for decoding
+const ALPHABET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789.,?'!/()&:;=+-_\"$@";
+
+/// Character for a pattern of dots and dashes, e.g. `"-.-"` is `K`
+pub fn decode(pattern: &str) -> Option<char> {
+    ALPHABET.chars().find(|&c| code(c) == Some(pattern))
+}
+
+/// Durations of the Morse elements and gaps, in milliseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timing {
+    /// Length of a dit and of the gap between elements of a character
+    pub dit_ms: u32,
+    /// Gap between characters of a word
+    pub char_gap_ms: u32,
+    /// Gap between words
+    pub word_gap_ms: u32,
+}
+
+impl Timing {
+    /// Standard timing at `wpm` words per minute
+    pub fn new(wpm: u32) -> Self {
+        let dit_ms = 1200 / wpm.max(1);
+        Self {
+            dit_ms,
+            char_gap_ms: 3 * dit_ms,
+            word_gap_ms: 7 * dit_ms,
+        }
+    }
+
+    /// Farnsworth timing: characters at `char_wpm`, overall speed `effective_wpm`.
+    ///
+    /// If the effective speed is not lower than the character speed this is
+    /// the same as [`Timing::new`].
+    pub fn farnsworth(char_wpm: u32, effective_wpm: u32) -> Self {
+        let char_wpm = char_wpm.max(1);
+        let effective_wpm = effective_wpm.max(1);
+        if effective_wpm >= char_wpm {
+            return Self::new(char_wpm);
+        }
+
+        // ARRL formula: total delay spread over the 19 gap units of "PARIS "
+        let (c, s) = (char_wpm as f32, effective_wpm as f32);
+        let delay_ms = 1000.0 * (60.0 * c - 37.2 * s) / (s * c);
+
+        Self {
+            dit_ms: 1200 / char_wpm,
+            char_gap_ms: (3.0 * delay_ms / 19.0) as u32,
+            word_gap_ms: (7.0 * delay_ms / 19.0) as u32,
+        }
+    }
+}
+
+/// Key the output on or off for a duration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Event {
+    /// Whether the output is keyed (tone or light on)
+    pub on: bool,
+    /// How long to stay in this state, in milliseconds
+    pub duration_ms: u32,
+}
+
+/// Iterator over the timing events of a text.
+///
+/// Characters without a Morse code are skipped; any whitespace separates
+/// words. No trailing gap is produced after the last element.
+pub struct Encoder<'a> {
+    chars: Peekable<Chars<'a>>,
+    code: &'static [u8],
+    index: usize,
+    timing: Timing,
+    pending_gap: Option<u32>,
+}
+
+impl<'a> Encoder<'a> {
+    /// Encode `text` with the given timing
+    pub fn new(text: &'a str, timing: Timing) -> Self {
+        Self {
+            chars: text.chars().peekable(),
+            code: &[],
+            index: 0,
+            timing,
+            pending_gap: None,
+        }
+    }
+
+    /// Gap after the current character, consuming whitespace and unknown characters
+    fn gap_after_character(&mut self) -> Option<u32> {
+        let mut word_break = false;
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() {
+                word_break = true;
+            } else if code(c).is_some() {
+                return Some(if word_break {
+                    self.timing.word_gap_ms
+                } else {
+                    self.timing.char_gap_ms
+                });
+            }
+            self.chars.next();
+        }
+        None
+    }
+}
+
+impl Iterator for Encoder<'_> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        if let Some(gap) = self.pending_gap.take() {
+            return Some(Event {
+                on: false,
+                duration_ms: gap,
+            });
+        }
+
+        // Load the next character with a Morse code
+        while self.index >= self.code.len() {
+            let c = self.chars.next()?;
+            if let Some(code) = code(c) {
+                self.code = code.as_bytes();
+                self.index = 0;
+            }
+        }
+
+        let element = self.code[self.index];
+        self.index += 1;
+
+        self.pending_gap = if self.index < self.code.len() {
+            Some(self.timing.dit_ms)
+        } else {
+            self.gap_after_character()
+        };
+
+        let units = if element == b'-' { 3 } else { 1 };
+        Some(Event {
+            on: true,
+            duration_ms: units * self.timing.dit_ms,
+        })
+    }
+}
+
+/// An output that can be keyed on and off
+pub trait Keyer {
+    /// Error returned when the output cannot be switched
+    type Error;
+
+    /// Switch the output on (tone or light) or off
+    fn key(&mut self, on: bool) -> Result<(), Self::Error>;
+}
+
+/// Active-high GPIO outputs such as the Grove LED
+impl<P: OutputPin> Keyer for P {
+    type Error = P::Error;
+
+    fn key(&mut self, on: bool) -> Result<(), Self::Error> {
+        self.set_state(on.into())
+    }
+}
+
+/// Active-low GPIO output, such as the onboard LED on GPIO15
+pub struct ActiveLow<P>(pub P);
+
+impl<P: OutputPin> Keyer for ActiveLow<P> {
+    type Error = P::Error;
+
+    fn key(&mut self, on: bool) -> Result<(), Self::Error> {
+        self.0.set_state((!on).into())
+    }
+}
+
+/// The Grove Buzzer sounding a fixed tone while keyed
+#[cfg(target_os = "none")]
+pub struct BuzzerKeyer<'b, 'a> {
+    buzzer: &'b mut Buzzer<'a>,
+    frequency_hz: u32,
+}
+
+#[cfg(target_os = "none")]
+impl<'b, 'a> BuzzerKeyer<'b, 'a> {
+    /// Key `buzzer` with a tone of `frequency_hz` (600-800 Hz is customary)
+    pub fn new(buzzer: &'b mut Buzzer<'a>, frequency_hz: u32) -> Self {
+        Self {
+            buzzer,
+            frequency_hz,
+        }
+    }
+}
+
+#[cfg(target_os = "none")]
+impl Keyer for BuzzerKeyer<'_, '_> {
+    type Error = buzzer::Error;
+
+    fn key(&mut self, on: bool) -> Result<(), Self::Error> {
+        if on {
+            self.buzzer.tone(self.frequency_hz)
+        } else {
+            self.buzzer.silence()
+        }
+    }
+}
+
+/// Send `text` on `keyer`, blocking until done. The output is left off.
+pub fn send<K: Keyer, D: DelayNs>(
+    text: &str,
+    timing: Timing,
+    keyer: &mut K,
+    delay: &mut D,
+) -> Result<(), K::Error> {
+    for event in Encoder::new(text, timing) {
+        keyer.key(event.on)?;
+        delay.delay_ms(event.duration_ms);
+    }
+    keyer.key(false)
+}
+
+/// Longest pattern the decoder buffers ('$' has seven elements)
+const MAX_ELEMENTS: usize = 7;
+
+/// Output of the [`Decoder`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decoded {
+    /// A complete character
+    Char(char),
+    /// A character ended but its pattern has no Morse code
+    Unknown,
+    /// The gap after the last character was long enough to end a word
+    WordSpace,
+}
+
+/// Adaptive decoder for a hand-keyed input.
+///
+/// Call [`Decoder::update`] regularly (every few milliseconds) with the key
+/// state and a millisecond timestamp. Key-down times shorter than two dits
+/// are dits, longer ones dahs; the dit length follows the sender with an
+/// exponential average. A key-up longer than two dits ends a character and
+/// one longer than five dits ends a word.
+#[derive(Clone, Debug)]
+pub struct Decoder {
+    dit_ms: u32,
+    debounce_ms: u32,
+    pressed: bool,
+    last_edge_ms: u32,
+    pattern: [u8; MAX_ELEMENTS],
+    len: usize,
+    in_word: bool,
+}
+
+impl Decoder {
+    /// Shortest dit the decoder adapts to (60 WPM)
+    pub const MIN_DIT_MS: u32 = 20;
+    /// Longest dit the decoder adapts to (2 WPM)
+    pub const MAX_DIT_MS: u32 = 600;
+
+    /// Create a decoder expecting an initial speed of `wpm` words per minute
+    pub fn new(wpm: u32) -> Self {
+        Self {
+            dit_ms: Timing::new(wpm)
+                .dit_ms
+                .clamp(Self::MIN_DIT_MS, Self::MAX_DIT_MS),
+            debounce_ms: 10,
+            pressed: false,
+            last_edge_ms: 0,
+            pattern: [0; MAX_ELEMENTS],
+            len: 0,
+            in_word: false,
+        }
+    }
+
+    /// Ignore key changes within `debounce_ms` of the previous one (default 10)
+    pub fn with_debounce(mut self, debounce_ms: u32) -> Self {
+        self.debounce_ms = debounce_ms;
+        self
+    }
+
+    /// Current dit length estimate in milliseconds
+    pub fn dit_ms(&self) -> u32 {
+        self.dit_ms
+    }
+
+    /// Current speed estimate in words per minute
+    pub fn wpm(&self) -> u32 {
+        1200 / self.dit_ms
+    }
+
+    /// Feed the key state at `now_ms`; returns a decoded character or word space
+    pub fn update(&mut self, pressed: bool, now_ms: u32) -> Option<Decoded> {
+        let elapsed = now_ms.wrapping_sub(self.last_edge_ms);
+
+        if pressed == self.pressed {
+            return if pressed { None } else { self.gap(elapsed) };
+        }
+
+        if elapsed < self.debounce_ms {
+            return None;
+        }
+
+        self.pressed = pressed;
+        self.last_edge_ms = now_ms;
+
+        if pressed {
+            // A gap ended: finish a character the caller did not poll in time
+            self.gap(elapsed)
+        } else {
+            self.element(elapsed);
+            None
+        }
+    }
+
+    /// Discard any partially keyed character
+    pub fn reset(&mut self) {
+        self.len = 0;
+        self.in_word = false;
+    }
+
+    /// Record a dit or dah of `duration_ms` and adapt the dit length
+    fn element(&mut self, duration_ms: u32) {
+        let (symbol, dit_estimate) = if duration_ms < 2 * self.dit_ms {
+            (b'.', duration_ms)
+        } else {
+            (b'-', duration_ms / 3)
+        };
+
+        self.dit_ms =
+            ((3 * self.dit_ms + dit_estimate) / 4).clamp(Self::MIN_DIT_MS, Self::MAX_DIT_MS);
+
+        if self.len < MAX_ELEMENTS {
+            self.pattern[self.len] = symbol;
+        }
+        // Overlong patterns are counted so they decode as unknown
+        self.len = self.len.saturating_add(1);
+    }
+
+    /// Handle a key-up lasting `elapsed_ms` so far
+    fn gap(&mut self, elapsed_ms: u32) -> Option<Decoded> {
+        if self.len > 0 && elapsed_ms >= 2 * self.dit_ms {
+            let decoded = self
+                .pattern
+                .get(..self.len)
+                .and_then(|pattern| core::str::from_utf8(pattern).ok())
+                .and_then(decode)
+                .map_or(Decoded::Unknown, Decoded::Char);
+            self.len = 0;
+            self.in_word = true;
+            Some(decoded)
+        } else if self.len == 0 && self.in_word && elapsed_ms >= 5 * self.dit_ms {
+            self.in_word = false;
+            Some(Decoded::WordSpace)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on(duration_ms: u32) -> Event {
+        Event {
+            on: true,
+            duration_ms,
+        }
+    }
+
+    fn off(duration_ms: u32) -> Event {
+        Event {
+            on: false,
+            duration_ms,
+        }
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        for c in ALPHABET.chars() {
+            assert_eq!(code(c).and_then(decode), Some(c));
+        }
+        assert_eq!(code('k'), Some("-.-"));
+        assert_eq!(code('#'), None);
+    }
+
+    #[test]
+    fn standard_timing() {
+        let timing = Timing::new(20);
+        assert_eq!(
+            timing,
+            Timing {
+                dit_ms: 60,
+                char_gap_ms: 180,
+                word_gap_ms: 420
+            }
+        );
+        assert_eq!(
+            Encoder::new("A", timing).collect::<Vec<_>>(),
+            [on(60), off(60), on(180)]
+        );
+    }
+
+    #[test]
+    fn character_and_word_gaps() {
+        let timing = Timing::new(20);
+        assert_eq!(
+            Encoder::new(" E#T  E ", timing).collect::<Vec<_>>(),
+            [on(60), off(180), on(180), off(420), on(60)]
+        );
+        assert_eq!(Encoder::new("# ", timing).count(), 0);
+    }
+
+    #[test]
+    fn farnsworth_stretches_gaps_only() {
+        assert_eq!(
+            Timing::farnsworth(20, 10),
+            Timing {
+                dit_ms: 60,
+                char_gap_ms: 653,
+                word_gap_ms: 1525
+            }
+        );
+        assert_eq!(Timing::farnsworth(10, 20), Timing::new(10));
+    }
+
+    /// Key `pattern` at `dit_ms` per dit from `start_ms`; returns the time of
+    /// the last key-up
+    fn key(decoder: &mut Decoder, pattern: &str, dit_ms: u32, start_ms: u32) -> u32 {
+        let mut now = start_ms;
+        for element in pattern.bytes() {
+            assert_eq!(decoder.update(true, now), None);
+            now += if element == b'-' { 3 * dit_ms } else { dit_ms };
+            assert_eq!(decoder.update(false, now), None);
+            now += dit_ms;
+        }
+        now - dit_ms
+    }
+
+    #[test]
+    fn decodes_characters_and_word_spaces() {
+        let mut decoder = Decoder::new(20);
+        let up = key(&mut decoder, "-.-", 60, 1000);
+        assert_eq!(decoder.update(false, up + 119), None);
+        assert_eq!(decoder.update(false, up + 120), Some(Decoded::Char('K')));
+        assert_eq!(decoder.update(false, up + 299), None);
+        assert_eq!(decoder.update(false, up + 300), Some(Decoded::WordSpace));
+        assert_eq!(decoder.update(false, up + 1000), None);
+    }
+
+    #[test]
+    fn unknown_and_overlong_patterns() {
+        let mut decoder = Decoder::new(20);
+        let up = key(&mut decoder, ".-.-", 60, 1000);
+        assert_eq!(decoder.update(false, up + 120), Some(Decoded::Unknown));
+        let up = key(&mut decoder, "........", 60, up + 200);
+        assert_eq!(decoder.update(false, up + 120), Some(Decoded::Unknown));
+    }
+
+    #[test]
+    fn ignores_bounces() {
+        let mut decoder = Decoder::new(20);
+        assert_eq!(decoder.update(true, 1000), None);
+        assert_eq!(decoder.update(false, 1005), None);
+        assert_eq!(decoder.update(false, 1060), None);
+        assert_eq!(decoder.update(false, 1180), Some(Decoded::Char('E')));
+    }
+
+    #[test]
+    fn adapts_to_the_sender() {
+        let mut decoder = Decoder::new(20);
+        assert_eq!(decoder.dit_ms(), 60);
+
+        // A faster sender, at 30 WPM
+        let mut now = 1000;
+        for _ in 0..10 {
+            let up = key(&mut decoder, ".", 40, now);
+            assert_eq!(decoder.update(false, up + 120), Some(Decoded::Char('E')));
+            now = up + 150;
+        }
+        assert_eq!(decoder.dit_ms(), 40);
+        assert_eq!(decoder.wpm(), 30);
+
+        // Dahs count as three dits; the rounded-down average settles a few
+        // milliseconds short when the sender slows down
+        for _ in 0..10 {
+            let up = key(&mut decoder, "-", 50, now);
+            assert_eq!(decoder.update(false, up + 110), Some(Decoded::Char('T')));
+            now = up + 180;
+        }
+        assert!((45..=50).contains(&decoder.dit_ms()));
+    }
+}
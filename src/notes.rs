@@ -0,0 +1,170 @@
+//! Musical notes and their equal-tempered frequencies
+//!
+//! Frequencies cover C0 to B8 with A4 = 440 Hz. Durations are expressed the
+//! way sheet music (and RTTTL) does: as a division of a whole note, with an
+//! optional dot, and converted to milliseconds for a tempo in beats per
+//! minute where one beat is a quarter note.
+
+/// One of the twelve semitones of an octave
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pitch {
+    C,
+    CSharp,
+    D,
+    DSharp,
+    E,
+    F,
+    FSharp,
+    G,
+    GSharp,
+    A,
+    ASharp,
+    B,
+}
+
+/// Lowest octave in the frequency table
+pub const MIN_OCTAVE: u8 = 0;
+/// Highest octave in the frequency table
+pub const MAX_OCTAVE: u8 = 8;
+
+/// Equal-tempered frequencies in Hz, C0 to B8
+#[rustfmt::skip]
+const FREQUENCIES_HZ: [f32; 108] = [
+    // Octave 0
+    16.35, 17.32, 18.35, 19.45, 20.60, 21.83, 23.12, 24.50, 25.96, 27.50, 29.14, 30.87,
+    // Octave 1
+    32.70, 34.65, 36.71, 38.89, 41.20, 43.65, 46.25, 49.00, 51.91, 55.00, 58.27, 61.74,
+    // Octave 2
+    65.41, 69.30, 73.42, 77.78, 82.41, 87.31, 92.50, 98.00, 103.83, 110.00, 116.54, 123.47,
+    // Octave 3
+    130.81, 138.59, 146.83, 155.56, 164.81, 174.61, 185.00, 196.00, 207.65, 220.00, 233.08, 246.94,
+    // Octave 4
+    261.63, 277.18, 293.66, 311.13, 329.63, 349.23, 369.99, 392.00, 415.30, 440.00, 466.16, 493.88,
+    // Octave 5
+    523.25, 554.37, 587.33, 622.25, 659.26, 698.46, 739.99, 783.99, 830.61, 880.00, 932.33, 987.77,
+    // Octave 6
+    1046.50, 1108.73, 1174.66, 1244.51, 1318.51, 1396.91, 1479.98, 1567.98, 1661.22, 1760.00, 1864.66, 1975.53,
+    // Octave 7
+    2093.00, 2217.46, 2349.32, 2489.02, 2637.02, 2793.83, 2959.96, 3135.96, 3322.44, 3520.00, 3729.31, 3951.07,
+    // Octave 8
+    4186.01, 4434.92, 4698.64, 4978.03, 5274.04, 5587.65, 5919.91, 6271.93, 6644.88, 7040.00, 7458.62, 7902.13,
+];
+
+impl Pitch {
+    /// All pitches of an octave in ascending order
+    pub const ALL: [Pitch; 12] = [
+        Pitch::C,
+        Pitch::CSharp,
+        Pitch::D,
+        Pitch::DSharp,
+        Pitch::E,
+        Pitch::F,
+        Pitch::FSharp,
+        Pitch::G,
+        Pitch::GSharp,
+        Pitch::A,
+        Pitch::ASharp,
+        Pitch::B,
+    ];
+
+    /// Frequency of this pitch in `octave` (clamped to 0-8), in Hz
+    pub fn frequency(self, octave: u8) -> f32 {
+        let octave = octave.clamp(MIN_OCTAVE, MAX_OCTAVE) as usize;
+        FREQUENCIES_HZ[octave * 12 + self as usize]
+    }
+
+    /// Pitch `semitones` above this one, with the octave carried along
+    pub fn transpose(self, octave: u8, semitones: i8) -> (Pitch, u8) {
+        let index = (octave as i16 * 12 + self as i16 + semitones as i16).clamp(0, 107);
+        (Pitch::ALL[(index % 12) as usize], (index / 12) as u8)
+    }
+}
+
+/// A note or rest with a musical duration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Note {
+    /// Pitch and octave (0-8), or `None` for a rest
+    pub tone: Option<(Pitch, u8)>,
+    /// Length as a division of a whole note: 1 = whole, 4 = quarter, 8 = eighth
+    pub division: u8,
+    /// Dotted notes last one and a half times as long
+    pub dotted: bool,
+}
+
+impl Note {
+    /// A note of `pitch` in `octave` lasting 1/`division` of a whole note
+    pub const fn new(pitch: Pitch, octave: u8, division: u8) -> Self {
+        Self {
+            tone: Some((pitch, octave)),
+            division,
+            dotted: false,
+        }
+    }
+
+    /// A rest lasting 1/`division` of a whole note
+    pub const fn rest(division: u8) -> Self {
+        Self {
+            tone: None,
+            division,
+            dotted: false,
+        }
+    }
+
+    /// The same note, dotted
+    pub const fn dotted(mut self) -> Self {
+        self.dotted = true;
+        self
+    }
+
+    /// Frequency in Hz, or `None` for a rest
+    pub fn frequency(&self) -> Option<f32> {
+        self.tone.map(|(pitch, octave)| pitch.frequency(octave))
+    }
+
+    /// Length in milliseconds at `bpm` quarter notes per minute
+    pub fn duration_ms(&self, bpm: u16) -> u32 {
+        let whole_ms = 4 * 60_000 / bpm.max(1) as u32;
+        let ms = whole_ms / self.division.max(1) as u32;
+        if self.dotted {
+            ms + ms / 2
+        } else {
+            ms
+        }
+    }
+}
+
+/// Chord types, built from semitone intervals above the root
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chord {
+    Major,
+    Minor,
+    Diminished,
+    Augmented,
+    Sus4,
+    Major7,
+    Minor7,
+    Dominant7,
+}
+
+impl Chord {
+    /// Semitones of each chord tone above the root, including the root
+    pub fn intervals(self) -> &'static [i8] {
+        match self {
+            Chord::Major => &[0, 4, 7],
+            Chord::Minor => &[0, 3, 7],
+            Chord::Diminished => &[0, 3, 6],
+            Chord::Augmented => &[0, 4, 8],
+            Chord::Sus4 => &[0, 5, 7],
+            Chord::Major7 => &[0, 4, 7, 11],
+            Chord::Minor7 => &[0, 3, 7, 10],
+            Chord::Dominant7 => &[0, 4, 7, 10],
+        }
+    }
+
+    /// Pitches and octaves of the chord built on `root` in `octave`
+    pub fn tones(self, root: Pitch, octave: u8) -> impl Iterator<Item = (Pitch, u8)> + Clone {
+        self.intervals()
+            .iter()
+            .map(move |&semitones| root.transpose(octave, semitones))
+    }
+}
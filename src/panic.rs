@@ -0,0 +1,299 @@
+//! LED error codes for panics
+//!
+//! Deployed boards rarely have a serial console attached. With the
+//! `led-panic` feature, the library's panic handler prints the panic message
+//! and backtrace like `esp_backtrace` does, then keeps blinking a [`Report`]
+//! on the onboard LED (GPIO15) until reset:
+//!
+//! - long blinks counting the panic [`Category`], then
+//! - three groups of short blinks, each 1-9, giving a location code hashed
+//!   from the source file and line of the panic.
+//!
+//! The same code is printed over serial as e.g. `LED code: 2-153`, so a code
+//! read from the LED can be matched against a panic seen on the bench.
+//!
+//! The feature replaces the handler of `esp_backtrace` and only takes effect
+//! without the default `backtrace-panic` feature, so build with
+//! `--no-default-features --features led-panic`. Every binary links this
+//! crate, so the handler is included in all of them.
+
+use core::{fmt, iter};
+
+use crate::morse::Event;
+
+/// Length of a category blink
+const LONG_MS: u32 = 800;
+/// Length of a location digit blink
+const SHORT_MS: u32 = 200;
+/// Pause between blinks of a group
+const BLINK_GAP_MS: u32 = 300;
+/// Pause between groups
+const GROUP_GAP_MS: u32 = 1200;
+/// Pause before the report repeats
+const REPEAT_GAP_MS: u32 = 4000;
+
+/// Kind of panic, told apart by the standard library message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    /// `unwrap()` on `None` or `Err`
+    Unwrap = 1,
+    /// Index or range out of bounds
+    Bounds = 2,
+    /// Arithmetic overflow or division by zero
+    Arithmetic = 3,
+    /// `unreachable!()`, `todo!()` or `unimplemented!()`
+    Unreachable = 4,
+    /// Any other panic, including `panic!()` and `expect()`
+    Other = 5,
+}
+
+impl Category {
+    /// Classify a panic by the start of its message
+    pub fn from_message(message: &str) -> Self {
+        const PREFIXES: [(&str, Category); 9] = [
+            ("called `Option::unwrap()`", Category::Unwrap),
+            ("called `Result::unwrap()`", Category::Unwrap),
+            ("index out of bounds", Category::Bounds),
+            ("range start index", Category::Bounds),
+            ("range end index", Category::Bounds),
+            ("attempt to ", Category::Arithmetic),
+            (
+                "internal error: entered unreachable code",
+                Category::Unreachable,
+            ),
+            ("not yet implemented", Category::Unreachable),
+            ("not implemented", Category::Unreachable),
+        ];
+
+        PREFIXES
+            .iter()
+            .find(|(prefix, _)| message.starts_with(prefix))
+            .map_or(Category::Other, |&(_, category)| category)
+    }
+}
+
+/// What the LED reports about a panic
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Report {
+    pub category: Category,
+    /// Location code digits, each 1-9 so every digit has at least one blink
+    pub location: [u8; 3],
+}
+
+impl Report {
+    /// Report a panic of `category` at `file`:`line`
+    pub fn new(category: Category, file: &str, line: u32) -> Self {
+        // 32-bit FNV-1a over the file name and line
+        let mut hash: u32 = 0x811c_9dc5;
+        for byte in file.bytes().chain(line.to_le_bytes()) {
+            hash = (hash ^ byte as u32).wrapping_mul(0x0100_0193);
+        }
+
+        let mut location = [0; 3];
+        for digit in &mut location {
+            *digit = (hash % 9) as u8 + 1;
+            hash /= 9;
+        }
+
+        Self { category, location }
+    }
+
+    /// Blink sequence of one repetition of the report
+    pub fn events(&self) -> impl Iterator<Item = Event> {
+        let category = (self.category as u8, LONG_MS);
+        let digits = self.location.map(|digit| (digit, SHORT_MS));
+
+        iter::once(category)
+            .chain(digits)
+            .flat_map(|(count, on_ms)| {
+                (0..count)
+                    .flat_map(move |_| [(true, on_ms), (false, BLINK_GAP_MS)])
+                    .chain(iter::once((false, GROUP_GAP_MS)))
+            })
+            .chain(iter::once((false, REPEAT_GAP_MS)))
+            .map(|(on, duration_ms)| Event { on, duration_ms })
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c] = self.location;
+        write!(f, "{}-{}{}{}", self.category as u8, a, b, c)
+    }
+}
+
+// `backtrace-panic` wins when both features are on, as with `--all-features`
+#[cfg(all(
+    feature = "led-panic",
+    not(feature = "backtrace-panic"),
+    target_os = "none"
+))]
+mod handler {
+    use core::{
+        fmt::{self, Write},
+        panic::PanicInfo,
+    };
+
+    use esp_hal::{
+        delay::Delay,
+        gpio::{Level, Output, OutputConfig},
+        peripherals::GPIO15,
+    };
+
+    use super::{Category, Report};
+    use crate::morse::{ActiveLow, Keyer};
+
+    /// Keeps the start of a formatted message, dropping the rest
+    struct Prefix {
+        buf: [u8; 64],
+        len: usize,
+    }
+
+    impl Prefix {
+        fn as_str(&self) -> &str {
+            // Truncation may split a character; keep the valid part
+            match core::str::from_utf8(&self.buf[..self.len]) {
+                Ok(s) => s,
+                Err(e) => core::str::from_utf8(&self.buf[..e.valid_up_to()]).unwrap_or(""),
+            }
+        }
+    }
+
+    impl Write for Prefix {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let n = s.len().min(self.buf.len() - self.len);
+            self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+            self.len += n;
+            Ok(())
+        }
+    }
+
+    #[panic_handler]
+    fn panic(info: &PanicInfo) -> ! {
+        esp_println::println!("");
+        esp_println::println!("====================== PANIC ======================");
+        esp_println::println!("{}", info);
+
+        let mut message = Prefix {
+            buf: [0; 64],
+            len: 0,
+        };
+        let _ = write!(message, "{}", info.message());
+        let category = Category::from_message(message.as_str());
+        let report = match info.location() {
+            Some(location) => Report::new(category, location.file(), location.line()),
+            None => Report::new(category, "", 0),
+        };
+        esp_println::println!("LED code: {}", report);
+
+        esp_println::println!("");
+        esp_println::println!("Backtrace:");
+        esp_println::println!("");
+        for frame in esp_backtrace::Backtrace::capture().frames() {
+            esp_println::println!("0x{:x}", frame.program_counter());
+        }
+
+        // The application owned the LED pin, but it will never run again
+        let pin = unsafe { GPIO15::steal() };
+        let mut led = ActiveLow(Output::new(pin, Level::High, OutputConfig::default()));
+        let delay = Delay::new();
+
+        loop {
+            for event in report.events() {
+                let _ = led.key(event.on);
+                delay.delay_millis(event.duration_ms);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categories() {
+        let cases = [
+            (
+                "called `Option::unwrap()` on a `None` value",
+                Category::Unwrap,
+            ),
+            (
+                "called `Result::unwrap()` on an `Err` value: ()",
+                Category::Unwrap,
+            ),
+            (
+                "index out of bounds: the len is 3 but the index is 5",
+                Category::Bounds,
+            ),
+            (
+                "range end index 9 out of range for slice of length 4",
+                Category::Bounds,
+            ),
+            ("attempt to add with overflow", Category::Arithmetic),
+            ("attempt to divide by zero", Category::Arithmetic),
+            (
+                "internal error: entered unreachable code",
+                Category::Unreachable,
+            ),
+            ("not yet implemented", Category::Unreachable),
+            ("sensor missing", Category::Other),
+            ("", Category::Other),
+        ];
+        for (message, category) in cases {
+            assert_eq!(Category::from_message(message), category, "{message}");
+        }
+    }
+
+    #[test]
+    fn location_digits_are_stable_and_non_zero() {
+        let report = Report::new(Category::Bounds, "src/main.rs", 42);
+        assert_eq!(report, Report::new(Category::Bounds, "src/main.rs", 42));
+        assert!(report
+            .location
+            .iter()
+            .all(|&digit| (1..=9).contains(&digit)));
+        assert_ne!(
+            report.location,
+            Report::new(Category::Bounds, "src/main.rs", 43).location
+        );
+    }
+
+    #[test]
+    fn display() {
+        let report = Report {
+            category: Category::Bounds,
+            location: [1, 5, 3],
+        };
+        assert_eq!(report.to_string(), "2-153");
+    }
+
+    #[test]
+    fn blink_sequence() {
+        let report = Report {
+            category: Category::Unwrap,
+            location: [2, 1, 1],
+        };
+        let events: Vec<(bool, u32)> = report
+            .events()
+            .map(|event| (event.on, event.duration_ms))
+            .collect();
+        let blink = |on_ms| [(true, on_ms), (false, BLINK_GAP_MS)];
+        let group_gap = [(false, GROUP_GAP_MS)];
+
+        let expected: Vec<(bool, u32)> = [
+            &blink(LONG_MS)[..],
+            &group_gap,
+            &blink(SHORT_MS),
+            &blink(SHORT_MS),
+            &group_gap,
+            &blink(SHORT_MS),
+            &group_gap,
+            &blink(SHORT_MS),
+            &group_gap,
+            &[(false, REPEAT_GAP_MS)],
+        ]
+        .concat();
+        assert_eq!(events, expected);
+    }
+}
@@ -0,0 +1,96 @@
+//! Current budget for RGB LED strips
+//!
+//! Every lit channel of a WS2812/WS2813 LED draws current, about 20 mA at
+//! full brightness, so all 15 LEDs of the Grove RGB LED Stick at full white
+//! need close to 1 A: more than USB or the 3.3 V regulator can supply.
+//! [`PowerLimit`] estimates the current of a frame before it is written and
+//! dims the whole frame evenly when it would exceed the budget:
+//!
+//! ```ignore
+//! let limit = PowerLimit::new(500);
+//! effect.render(now_ms(), &mut frame);
+//! limit.apply(&mut frame);
+//! leds.write(frame.iter().cloned())?;
+//! ```
+//!
+//! The estimate assumes current grows linearly with the channel value, which
+//! is close enough for the PWM-driven LEDs.
+
+use smart_leds::RGB8;
+
+/// Current of one channel at full brightness, typical for WS2812/WS2813
+pub const DEFAULT_CHANNEL_MA: u32 = 20;
+/// Current of one LED with all channels off
+pub const DEFAULT_IDLE_MA: u32 = 1;
+
+/// Scales frames down to stay within a current budget
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PowerLimit {
+    budget_ma: u32,
+    channel_ma: u32,
+    idle_ma: u32,
+}
+
+impl PowerLimit {
+    /// Limit frames to `budget_ma` milliamps, e.g. 500 for USB
+    pub fn new(budget_ma: u32) -> Self {
+        Self {
+            budget_ma,
+            channel_ma: DEFAULT_CHANNEL_MA,
+            idle_ma: DEFAULT_IDLE_MA,
+        }
+    }
+
+    /// Set the current of one channel (R, G or B) at full brightness
+    pub fn with_channel_ma(mut self, channel_ma: u32) -> Self {
+        self.channel_ma = channel_ma;
+        self
+    }
+
+    /// Set the quiescent current of one LED
+    pub fn with_idle_ma(mut self, idle_ma: u32) -> Self {
+        self.idle_ma = idle_ma;
+        self
+    }
+
+    /// Budget in milliamps
+    pub fn budget_ma(&self) -> u32 {
+        self.budget_ma
+    }
+
+    /// Estimated current of `frame` in milliamps
+    pub fn estimate_ma(&self, frame: &[RGB8]) -> u32 {
+        self.idle_ma * frame.len() as u32 + self.channels_ma(frame)
+    }
+
+    /// Dim `frame` evenly so it stays within the budget.
+    ///
+    /// Returns the brightness scale that was applied (255 if the frame was
+    /// within the budget already).
+    pub fn apply(&self, frame: &mut [RGB8]) -> u8 {
+        let channels_ma = self.channels_ma(frame);
+        let available_ma = self
+            .budget_ma
+            .saturating_sub(self.idle_ma * frame.len() as u32);
+        if channels_ma <= available_ma {
+            return u8::MAX;
+        }
+
+        // Rounding down keeps the dimmed frame within the budget
+        let scale = (available_ma * 255 / channels_ma) as u8;
+        for pixel in frame.iter_mut() {
+            let channel = |c: u8| (c as u32 * scale as u32 / 255) as u8;
+            *pixel = RGB8::new(channel(pixel.r), channel(pixel.g), channel(pixel.b));
+        }
+        scale
+    }
+
+    /// Current of the lit channels of `frame` in milliamps, rounded up
+    fn channels_ma(&self, frame: &[RGB8]) -> u32 {
+        let sum: u32 = frame
+            .iter()
+            .map(|pixel| pixel.r as u32 + pixel.g as u32 + pixel.b as u32)
+            .sum();
+        (sum * self.channel_ma).div_ceil(255)
+    }
+}
@@ -0,0 +1,99 @@
+//! Fixed-capacity FIFO queue
+//!
+//! [`Queue`] holds up to `N` items without allocating, for events produced
+//! faster than one per call, such as a button release that also completes a
+//! double click. When full it refuses new items, keeping the oldest ones.
+
+/// First-in first-out queue of up to `N` items
+#[derive(Clone, Debug)]
+pub struct Queue<T, const N: usize> {
+    items: [Option<T>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T, const N: usize> Default for Queue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> Queue<T, N> {
+    /// Create an empty queue, usable in a `static`
+    pub const fn new() -> Self {
+        Self {
+            items: [const { None }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Add an item at the back; returns false (dropping `item`) when full
+    pub fn push(&mut self, item: T) -> bool {
+        if self.len == N {
+            return false;
+        }
+        self.items[(self.head + self.len) % N] = Some(item);
+        self.len += 1;
+        true
+    }
+
+    /// Remove the item at the front
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        let item = self.items[self.head].take();
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        item
+    }
+
+    /// Number of items waiting
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no items are waiting
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Whether no more items fit
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_in_first_out_across_wrap_around() {
+        let mut queue = Queue::<u32, 3>::new();
+        assert!(queue.push(1));
+        assert!(queue.push(2));
+        assert_eq!(queue.pop(), Some(1));
+        assert!(queue.push(3));
+        assert!(queue.push(4));
+        assert!(queue.is_full());
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), Some(3));
+        assert_eq!(queue.pop(), Some(4));
+        assert_eq!(queue.pop(), None);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn full_queue_keeps_the_oldest() {
+        let mut queue = Queue::<u32, 2>::new();
+        assert!(queue.push(1));
+        assert!(queue.push(2));
+        assert!(!queue.push(3));
+        assert_eq!(queue.pop(), Some(1));
+        assert_eq!(queue.pop(), Some(2));
+        assert_eq!(queue.pop(), None);
+    }
+}
@@ -0,0 +1,319 @@
+//! RTTTL (Nokia ringtone) parser
+//!
+//! A ringtone has three sections separated by colons:
+//!
+//! ```text
+//! Nokia:d=4,o=5,b=225:8e6,8d6,f#,g#,8c#6,8b,d,e,8b,8a,c#,e,2a
+//! ```
+//!
+//! - the name,
+//! - the defaults: note duration `d=`, octave `o=` and tempo `b=` in beats
+//!   per minute,
+//! - comma-separated notes written as `[duration]letter[#][.][octave][.]`,
+//!   where `p` is a pause and `h` is an alias for `b`.
+//!
+//! Parsing does not allocate: [`Rtttl::notes`] returns an iterator that
+//! decodes the notes lazily from the original string.
+
+use crate::notes::{Note, Pitch, MAX_OCTAVE};
+
+/// Errors while parsing a ringtone
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The ringtone does not have three `:` separated sections
+    MissingSection,
+    /// A `d=`, `o=` or `b=` default is malformed or out of range
+    InvalidDefault,
+    /// A note could not be parsed
+    InvalidNote,
+}
+
+/// Defaults applied to notes that do not specify their own values
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Defaults {
+    /// Note duration as a division of a whole note
+    pub duration: u8,
+    /// Octave
+    pub octave: u8,
+    /// Tempo in beats (quarter notes) per minute
+    pub bpm: u16,
+}
+
+impl Default for Defaults {
+    // Values defined by the RTTTL specification
+    fn default() -> Self {
+        Self {
+            duration: 4,
+            octave: 6,
+            bpm: 63,
+        }
+    }
+}
+
+/// A parsed ringtone
+#[derive(Clone, Copy, Debug)]
+pub struct Rtttl<'a> {
+    /// Name of the ringtone
+    pub name: &'a str,
+    /// Defaults from the second section
+    pub defaults: Defaults,
+    notes: &'a str,
+}
+
+impl<'a> Rtttl<'a> {
+    /// Parse the name and defaults of a ringtone.
+    ///
+    /// Notes are only validated when iterated with [`Rtttl::notes`].
+    pub fn parse(ringtone: &'a str) -> Result<Self, Error> {
+        let mut sections = ringtone.splitn(3, ':');
+        let name = sections.next().ok_or(Error::MissingSection)?.trim();
+        let defaults = sections.next().ok_or(Error::MissingSection)?;
+        let notes = sections.next().ok_or(Error::MissingSection)?;
+
+        Ok(Self {
+            name,
+            defaults: parse_defaults(defaults)?,
+            notes,
+        })
+    }
+
+    /// Tempo in beats per minute
+    pub fn bpm(&self) -> u16 {
+        self.defaults.bpm
+    }
+
+    /// Iterate over the notes of the ringtone
+    pub fn notes(&self) -> Notes<'a> {
+        Notes {
+            parts: self.notes.split(','),
+            defaults: self.defaults,
+        }
+    }
+}
+
+/// Iterator over the notes of a ringtone
+#[derive(Clone, Debug)]
+pub struct Notes<'a> {
+    parts: core::str::Split<'a, char>,
+    defaults: Defaults,
+}
+
+impl Iterator for Notes<'_> {
+    type Item = Result<Note, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let part = self.parts.next()?.trim();
+            // Tolerate empty entries such as a trailing comma
+            if !part.is_empty() {
+                return Some(parse_note(part, &self.defaults));
+            }
+        }
+    }
+}
+
+fn parse_defaults(section: &str) -> Result<Defaults, Error> {
+    let mut defaults = Defaults::default();
+
+    for entry in section.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (key, value) = entry.split_once('=').ok_or(Error::InvalidDefault)?;
+        let value: u16 = value.trim().parse().map_err(|_| Error::InvalidDefault)?;
+
+        match key.trim() {
+            "d" | "D" if is_valid_duration(value) => defaults.duration = value as u8,
+            "o" | "O" if value <= MAX_OCTAVE as u16 => defaults.octave = value as u8,
+            "b" | "B" if value > 0 => defaults.bpm = value,
+            _ => return Err(Error::InvalidDefault),
+        }
+    }
+
+    Ok(defaults)
+}
+
+fn parse_note(text: &str, defaults: &Defaults) -> Result<Note, Error> {
+    let bytes = text.as_bytes();
+    let mut i = 0;
+
+    // Optional duration
+    let (duration, len) = parse_number(&bytes[i..]);
+    i += len;
+    let division = match duration {
+        Some(d) if is_valid_duration(d) => d as u8,
+        Some(_) => return Err(Error::InvalidNote),
+        None => defaults.duration,
+    };
+
+    // Note letter
+    let letter = bytes.get(i).ok_or(Error::InvalidNote)?.to_ascii_lowercase();
+    i += 1;
+
+    // Optional sharp
+    let sharp = bytes.get(i) == Some(&b'#');
+    if sharp {
+        i += 1;
+    }
+
+    // The dot may come before or after the octave
+    let mut dotted = bytes.get(i) == Some(&b'.');
+    if dotted {
+        i += 1;
+    }
+
+    let (octave, len) = parse_number(&bytes[i..]);
+    i += len;
+    let octave = match octave {
+        Some(o) if o <= MAX_OCTAVE as u16 => o as u8,
+        Some(_) => return Err(Error::InvalidNote),
+        None => defaults.octave,
+    };
+
+    if bytes.get(i) == Some(&b'.') {
+        dotted = true;
+        i += 1;
+    }
+
+    if i != bytes.len() {
+        return Err(Error::InvalidNote);
+    }
+
+    let pitch = match (letter, sharp) {
+        (b'p', false) => None,
+        (b'c', false) => Some(Pitch::C),
+        (b'c', true) => Some(Pitch::CSharp),
+        (b'd', false) => Some(Pitch::D),
+        (b'd', true) => Some(Pitch::DSharp),
+        (b'e', false) => Some(Pitch::E),
+        (b'f', false) => Some(Pitch::F),
+        (b'f', true) => Some(Pitch::FSharp),
+        (b'g', false) => Some(Pitch::G),
+        (b'g', true) => Some(Pitch::GSharp),
+        (b'a', false) => Some(Pitch::A),
+        (b'a', true) => Some(Pitch::ASharp),
+        (b'b' | b'h', false) => Some(Pitch::B),
+        _ => return Err(Error::InvalidNote),
+    };
+
+    let note = match pitch {
+        Some(pitch) => Note::new(pitch, octave, division),
+        None => Note::rest(division),
+    };
+
+    Ok(if dotted { note.dotted() } else { note })
+}
+
+/// Parse leading ASCII digits, returning the value and the number of bytes used
+fn parse_number(bytes: &[u8]) -> (Option<u16>, usize) {
+    let len = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    if len == 0 {
+        return (None, 0);
+    }
+
+    let value = bytes[..len].iter().try_fold(0u16, |acc, &b| {
+        acc.checked_mul(10)?.checked_add((b - b'0') as u16)
+    });
+    // Overflowing numbers are out of range for every field
+    (Some(value.unwrap_or(u16::MAX)), len)
+}
+
+fn is_valid_duration(duration: u16) -> bool {
+    matches!(duration, 1 | 2 | 4 | 8 | 16 | 32 | 64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notes(ringtone: &str) -> Result<Vec<Note>, Error> {
+        Rtttl::parse(ringtone)?.notes().collect()
+    }
+
+    #[test]
+    fn defaults_when_section_is_empty() {
+        let rtttl = Rtttl::parse("Empty::c").unwrap();
+        assert_eq!(rtttl.name, "Empty");
+        assert_eq!(
+            rtttl.defaults,
+            Defaults {
+                duration: 4,
+                octave: 6,
+                bpm: 63
+            }
+        );
+        assert_eq!(notes("Empty::c").unwrap(), [Note::new(Pitch::C, 6, 4)]);
+    }
+
+    #[test]
+    fn explicit_defaults() {
+        let rtttl = Rtttl::parse("Nokia:d=8, o=5, b=225:e").unwrap();
+        assert_eq!(
+            rtttl.defaults,
+            Defaults {
+                duration: 8,
+                octave: 5,
+                bpm: 225
+            }
+        );
+        assert_eq!(rtttl.bpm(), 225);
+        assert_eq!(
+            notes("Nokia:d=8,o=5,b=225:e,4f#6").unwrap(),
+            [Note::new(Pitch::E, 5, 8), Note::new(Pitch::FSharp, 6, 4)]
+        );
+    }
+
+    #[test]
+    fn dot_before_or_after_octave() {
+        let dotted = Note::new(Pitch::CSharp, 6, 8).dotted();
+        assert_eq!(notes("x:d=4,o=5,b=100:8c#.6").unwrap(), [dotted]);
+        assert_eq!(notes("x:d=4,o=5,b=100:8c#6.").unwrap(), [dotted]);
+    }
+
+    #[test]
+    fn h_is_b() {
+        assert_eq!(
+            notes("x:o=5:h,8h4").unwrap(),
+            [Note::new(Pitch::B, 5, 4), Note::new(Pitch::B, 4, 8)]
+        );
+    }
+
+    #[test]
+    fn pauses() {
+        assert_eq!(
+            notes("x:d=4:p,2p.").unwrap(),
+            [Note::rest(4), Note::rest(2).dotted()]
+        );
+    }
+
+    #[test]
+    fn trailing_comma() {
+        assert_eq!(
+            notes("x:d=4,o=5:a,b,").unwrap(),
+            [Note::new(Pitch::A, 5, 4), Note::new(Pitch::B, 5, 4)]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Rtttl::parse("x:d=4").unwrap_err(), Error::MissingSection);
+        assert_eq!(Rtttl::parse("x").unwrap_err(), Error::MissingSection);
+        assert_eq!(Rtttl::parse("x:d=3:c").unwrap_err(), Error::InvalidDefault);
+        assert_eq!(Rtttl::parse("x:o=9:c").unwrap_err(), Error::InvalidDefault);
+        assert_eq!(Rtttl::parse("x:b=0:c").unwrap_err(), Error::InvalidDefault);
+        assert_eq!(Rtttl::parse("x:q=4:c").unwrap_err(), Error::InvalidDefault);
+        assert_eq!(Rtttl::parse("x:d:c").unwrap_err(), Error::InvalidDefault);
+        assert_eq!(notes("x::c,k").unwrap_err(), Error::InvalidNote);
+        assert_eq!(notes("x::3c").unwrap_err(), Error::InvalidNote);
+        assert_eq!(notes("x::c9").unwrap_err(), Error::InvalidNote);
+        assert_eq!(notes("x::e#").unwrap_err(), Error::InvalidNote);
+        assert_eq!(notes("x::c5x").unwrap_err(), Error::InvalidNote);
+    }
+
+    #[test]
+    fn duration_at_tempo() {
+        let rtttl = Rtttl::parse("x:d=4,o=5,b=120:c,8c,2c.,16p").unwrap();
+        let durations: Vec<u32> = rtttl
+            .notes()
+            .map(|note| note.unwrap().duration_ms(rtttl.bpm()))
+            .collect();
+        assert_eq!(durations, [500, 250, 1500, 125]);
+    }
+}
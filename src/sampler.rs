@@ -0,0 +1,205 @@
+//! Fixed-rate ADC sampling into a double buffer
+//!
+//! `read_oneshot` in a loop with `delay_millis` gives a slow and jittery
+//! sample rate. [`AdcSampler`] instead paces conversions from a periodic
+//! hardware timer interrupt and collects them into a [`DoubleBuffer`]:
+//! while the interrupt fills one half, the other half holds the last
+//! complete block for the application to pick up.
+//!
+//! The sampler is meant to live in a `critical_section::Mutex` shared
+//! between the timer interrupt handler and the main loop:
+//!
+//! ```ignore
+//! static SAMPLER: Mutex<RefCell<Option<AdcSampler<'static, ADC1<'static>, GPIO0<'static>, AdcCalLine<ADC1<'static>>, 256>>>> =
+//!     Mutex::new(RefCell::new(None));
+//!
+//! #[handler]
+//! fn sample_tick() {
+//!     critical_section::with(|cs| {
+//!         if let Some(sampler) = SAMPLER.borrow_ref_mut(cs).as_mut() {
+//!             sampler.on_interrupt();
+//!         }
+//!     });
+//! }
+//! ```
+//!
+//! Store the sampler before starting it, and call [`AdcSampler::start`] on
+//! the stored instance: an interrupt that finds the slot empty cannot clear
+//! the timer and would fire again forever.
+
+use esp_hal::{
+    analog::adc::{Adc, AdcCalScheme, AdcChannel, AdcPin, RegisterAccess},
+    time::Rate,
+    timer::{Error, PeriodicTimer},
+    Blocking,
+};
+
+/// Two sample blocks of `N` readings: one being filled, one ready for reading.
+pub struct DoubleBuffer<const N: usize> {
+    buffers: [[u16; N]; 2],
+    // Index of the buffer currently being written
+    active: usize,
+    // Write position inside the active buffer
+    position: usize,
+    // Set when the inactive buffer holds a block that was not taken yet
+    ready: bool,
+    overruns: u32,
+}
+
+impl<const N: usize> DoubleBuffer<N> {
+    /// Create an empty double buffer; `N = 0` is rejected at compile time
+    pub const fn new() -> Self {
+        const { assert!(N > 0, "sample blocks need at least one reading") };
+        Self {
+            buffers: [[0; N]; 2],
+            active: 0,
+            position: 0,
+            ready: false,
+            overruns: 0,
+        }
+    }
+
+    /// Append a sample to the active buffer.
+    ///
+    /// Returns `true` when the sample completed a block and the buffers were
+    /// swapped. If the previous block was never taken it is overwritten and
+    /// the overrun counter is incremented.
+    pub fn push(&mut self, sample: u16) -> bool {
+        self.buffers[self.active][self.position] = sample;
+        self.position += 1;
+
+        if self.position < N {
+            return false;
+        }
+
+        if self.ready {
+            self.overruns = self.overruns.wrapping_add(1);
+        }
+        self.active ^= 1;
+        self.position = 0;
+        self.ready = true;
+        true
+    }
+
+    /// Copy the last complete block into `out`.
+    ///
+    /// Returns `false` if no new block has been completed since the last call.
+    pub fn take(&mut self, out: &mut [u16; N]) -> bool {
+        if !self.ready {
+            return false;
+        }
+        out.copy_from_slice(&self.buffers[self.active ^ 1]);
+        self.ready = false;
+        true
+    }
+
+    /// Whether a complete block is waiting to be taken
+    pub fn is_ready(&self) -> bool {
+        self.ready
+    }
+
+    /// Number of blocks dropped because the application did not keep up
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    /// Discard any partial and pending data
+    pub fn reset(&mut self) {
+        self.position = 0;
+        self.ready = false;
+    }
+}
+
+impl<const N: usize> Default for DoubleBuffer<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Timer-paced ADC sampler for a single analog pin.
+///
+/// Call [`AdcSampler::on_interrupt`] from the interrupt handler bound to the
+/// timer, and [`AdcSampler::take`] from application code to receive blocks
+/// of `N` samples. Sample values are whatever the pin's calibration scheme
+/// returns (raw counts for `()`, millivolts for `AdcCalLine` and friends).
+pub struct AdcSampler<'d, ADCI, PIN, CS, const N: usize> {
+    adc: Adc<'d, ADCI, Blocking>,
+    pin: AdcPin<PIN, ADCI, CS>,
+    timer: PeriodicTimer<'d, Blocking>,
+    rate: Rate,
+    buffer: DoubleBuffer<N>,
+    errors: u32,
+}
+
+impl<'d, ADCI, PIN, CS, const N: usize> AdcSampler<'d, ADCI, PIN, CS, N>
+where
+    ADCI: RegisterAccess + 'd,
+    PIN: AdcChannel,
+    CS: AdcCalScheme<ADCI>,
+{
+    /// Create a sampler. The timer must already have its interrupt handler set.
+    pub fn new(
+        adc: Adc<'d, ADCI, Blocking>,
+        pin: AdcPin<PIN, ADCI, CS>,
+        timer: PeriodicTimer<'d, Blocking>,
+        rate: Rate,
+    ) -> Self {
+        Self {
+            adc,
+            pin,
+            timer,
+            rate,
+            buffer: DoubleBuffer::new(),
+            errors: 0,
+        }
+    }
+
+    /// Start sampling at the configured rate
+    pub fn start(&mut self) -> Result<(), Error> {
+        self.buffer.reset();
+        self.timer.start(self.rate.as_duration())?;
+        self.timer.listen();
+        Ok(())
+    }
+
+    /// Stop sampling. Pending data is kept until taken.
+    pub fn stop(&mut self) -> Result<(), Error> {
+        self.timer.unlisten();
+        self.timer.cancel()
+    }
+
+    /// Acquire one sample. Must be called from the timer interrupt handler.
+    ///
+    /// Returns `true` when a block of `N` samples became ready.
+    pub fn on_interrupt(&mut self) -> bool {
+        self.timer.clear_interrupt();
+
+        match nb::block!(self.adc.read_oneshot(&mut self.pin)) {
+            Ok(sample) => self.buffer.push(sample),
+            Err(_) => {
+                self.errors = self.errors.wrapping_add(1);
+                false
+            }
+        }
+    }
+
+    /// Copy the last complete block into `out`, see [`DoubleBuffer::take`]
+    pub fn take(&mut self, out: &mut [u16; N]) -> bool {
+        self.buffer.take(out)
+    }
+
+    /// Configured sample rate
+    pub fn rate(&self) -> Rate {
+        self.rate
+    }
+
+    /// Number of blocks dropped because they were not taken in time
+    pub fn overruns(&self) -> u32 {
+        self.buffer.overruns()
+    }
+
+    /// Number of failed conversions
+    pub fn errors(&self) -> u32 {
+        self.errors
+    }
+}
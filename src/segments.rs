@@ -0,0 +1,300 @@
+//! Number formatting for 4-digit seven-segment displays (TM1637)
+//!
+//! [`Formatter`] renders readings into the four segment bytes the TM1637
+//! expects, one per digit from left to right, so they can be written with
+//! `display_slice` and compared byte for byte:
+//!
+//! - fixed-point numbers with a chosen number of decimals (`" 3.14"`),
+//! - temperatures with a degree symbol (`"25°C"`, `"102°"`),
+//! - clock times and countdowns with a colon (`"12:34"`),
+//! - signed integers (`"-123"`).
+//!
+//! Values that do not fit are shown as `"----"`, or as `"HI"`/`"LO"` with
+//! [`Overflow::HiLo`]. The Grove 4-Digit Display has a colon after the second
+//! digit but no decimal points; displays with decimal points light them with
+//! the same bit ([`DOT`]).
+//!
+//! ```ignore
+//! let format = Formatter::new().with_overflow(Overflow::HiLo);
+//! tm.display_slice(0, &format.temperature(celsius, Unit::Celsius))?;
+//! tm.display_slice(0, &format.clock(hours, minutes, seconds % 2 == 0))?;
+//! ```
+
+/// Segment bytes for the four digits, from left to right
+pub type Segments = [u8; 4];
+
+/// Segments of the digits 0-9 (bit 0 is segment A, bit 6 segment G)
+pub const DIGITS: [u8; 10] = [
+    0b0011_1111,
+    0b0000_0110,
+    0b0101_1011,
+    0b0100_1111,
+    0b0110_0110,
+    0b0110_1101,
+    0b0111_1101,
+    0b0000_0111,
+    0b0111_1111,
+    0b0110_1111,
+];
+/// Unlit digit
+pub const BLANK: u8 = 0;
+/// Minus sign
+pub const MINUS: u8 = 0b0100_0000;
+/// Degree symbol
+pub const DEGREE: u8 = 0b0110_0011;
+/// Decimal point after a digit, or the colon after the second digit
+pub const DOT: u8 = 0b1000_0000;
+
+const C: u8 = 0b0011_1001;
+const F: u8 = 0b0111_0001;
+const H: u8 = 0b0111_0110;
+const I: u8 = 0b0000_0110;
+const L: u8 = 0b0011_1000;
+const O: u8 = 0b0011_1111;
+
+/// Digit that carries the colon of a clock display
+const COLON_DIGIT: usize = 1;
+
+/// How values that do not fit are shown
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// `"----"`
+    #[default]
+    Dashes,
+    /// `"HI"` above the range, `"LO"` below it
+    HiLo,
+}
+
+/// Temperature unit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unit {
+    Celsius,
+    Fahrenheit,
+}
+
+/// Formats numbers into segment bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Formatter {
+    overflow: Overflow,
+}
+
+impl Formatter {
+    /// Create a formatter showing overflows as `"----"`
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set how values that do not fit are shown
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Right-aligned signed integer, -999 to 9999
+    pub fn integer(&self, value: i32) -> Segments {
+        let mut segments = [BLANK; 4];
+        if number(value, 1, &mut segments) {
+            segments
+        } else {
+            self.overflow_for(value > 0)
+        }
+    }
+
+    /// Right-aligned number with `decimals` (0-3) digits after the decimal
+    /// point, e.g. `fixed(3.14159, 2)` shows `" 3.14"`
+    pub fn fixed(&self, value: f32, decimals: usize) -> Segments {
+        let decimals = decimals.min(3);
+        if value.is_nan() {
+            return self.overflow_for(true);
+        }
+
+        let scaled = libm::roundf(value * libm::powf(10.0, decimals as f32)) as i32;
+        let mut segments = [BLANK; 4];
+        if !number(scaled, decimals + 1, &mut segments) {
+            return self.overflow_for(value > 0.0);
+        }
+        if decimals > 0 {
+            segments[3 - decimals] |= DOT;
+        }
+        segments
+    }
+
+    /// Temperature rounded to whole degrees: `"25°C"`, or `"102°"` when the
+    /// value needs three digits
+    pub fn temperature(&self, celsius: f32, unit: Unit) -> Segments {
+        if celsius.is_nan() {
+            return self.overflow_for(true);
+        }
+
+        let (value, symbol) = match unit {
+            Unit::Celsius => (celsius, C),
+            Unit::Fahrenheit => (celsius * 9.0 / 5.0 + 32.0, F),
+        };
+        let value = libm::roundf(value) as i32;
+
+        let mut segments = [BLANK; 4];
+        if number(value, 1, &mut segments[..2]) {
+            segments[2] = DEGREE;
+            segments[3] = symbol;
+        } else if number(value, 1, &mut segments[..3]) {
+            segments[3] = DEGREE;
+        } else {
+            return self.overflow_for(value > 0);
+        }
+        segments
+    }
+
+    /// Clock time `"HH:MM"`; toggle `colon` every second to blink it
+    pub fn clock(&self, hours: u32, minutes: u32, colon: bool) -> Segments {
+        if hours > 99 || minutes > 59 {
+            return self.overflow_for(true);
+        }
+
+        let mut segments = [
+            DIGITS[(hours / 10) as usize],
+            DIGITS[(hours % 10) as usize],
+            DIGITS[(minutes / 10) as usize],
+            DIGITS[(minutes % 10) as usize],
+        ];
+        if colon {
+            segments[COLON_DIGIT] |= DOT;
+        }
+        segments
+    }
+
+    /// Time left as `"MM:SS"` below 100 minutes, then as `"HH:MM"`
+    pub fn countdown(&self, seconds: u32, colon: bool) -> Segments {
+        if seconds < 100 * 60 {
+            self.clock(seconds / 60, seconds % 60, colon)
+        } else {
+            self.clock(seconds / 3600, seconds / 60 % 60, colon)
+        }
+    }
+
+    /// Overflow indication for values above (`high`) or below the range
+    fn overflow_for(&self, high: bool) -> Segments {
+        match self.overflow {
+            Overflow::Dashes => [MINUS; 4],
+            Overflow::HiLo if high => [H, I, BLANK, BLANK],
+            Overflow::HiLo => [L, O, BLANK, BLANK],
+        }
+    }
+}
+
+/// Write `value` right-aligned into `cells` with at least `min_digits`
+/// digits; returns `false` if it does not fit
+fn number(value: i32, min_digits: usize, cells: &mut [u8]) -> bool {
+    let mut magnitude = value.unsigned_abs();
+    let mut i = cells.len();
+    let mut digits = 0;
+
+    while magnitude > 0 || digits < min_digits {
+        if i == 0 {
+            return false;
+        }
+        i -= 1;
+        cells[i] = DIGITS[(magnitude % 10) as usize];
+        magnitude /= 10;
+        digits += 1;
+    }
+
+    if value < 0 {
+        if i == 0 {
+            return false;
+        }
+        i -= 1;
+        cells[i] = MINUS;
+    }
+
+    cells[..i].fill(BLANK);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HI: Segments = [H, I, BLANK, BLANK];
+    const LO: Segments = [L, O, BLANK, BLANK];
+
+    fn d(digit: usize) -> u8 {
+        DIGITS[digit]
+    }
+
+    #[test]
+    fn integer() {
+        let format = Formatter::new();
+        assert_eq!(format.integer(9999), [d(9), d(9), d(9), d(9)]);
+        assert_eq!(format.integer(-999), [MINUS, d(9), d(9), d(9)]);
+        assert_eq!(format.integer(-12), [BLANK, MINUS, d(1), d(2)]);
+        assert_eq!(format.integer(0), [BLANK, BLANK, BLANK, d(0)]);
+    }
+
+    #[test]
+    fn integer_overflow() {
+        let dashes = Formatter::new();
+        let hi_lo = Formatter::new().with_overflow(Overflow::HiLo);
+        assert_eq!(dashes.integer(10000), [MINUS; 4]);
+        assert_eq!(dashes.integer(-1000), [MINUS; 4]);
+        assert_eq!(hi_lo.integer(10000), HI);
+        assert_eq!(hi_lo.integer(-1000), LO);
+    }
+
+    #[test]
+    fn fixed() {
+        let format = Formatter::new();
+        #[allow(clippy::approx_constant)]
+        let pi = 3.14159;
+        assert_eq!(format.fixed(pi, 2), [BLANK, d(3) | DOT, d(1), d(4)]);
+        assert_eq!(format.fixed(-0.04, 1), [BLANK, BLANK, d(0) | DOT, d(0)]);
+        assert_eq!(format.fixed(-0.06, 1), [BLANK, MINUS, d(0) | DOT, d(1)]);
+        assert_eq!(format.fixed(0.5, 3), [d(0) | DOT, d(5), d(0), d(0)]);
+        assert_eq!(format.fixed(12.0, 0), [BLANK, BLANK, d(1), d(2)]);
+        assert_eq!(format.fixed(f32::NAN, 1), [MINUS; 4]);
+        assert_eq!(format.with_overflow(Overflow::HiLo).fixed(100.0, 2), HI);
+    }
+
+    #[test]
+    fn temperature() {
+        let format = Formatter::new();
+        assert_eq!(
+            format.temperature(5.0, Unit::Celsius),
+            [BLANK, d(5), DEGREE, C]
+        );
+        assert_eq!(
+            format.temperature(-15.0, Unit::Celsius),
+            [MINUS, d(1), d(5), DEGREE]
+        );
+        assert_eq!(
+            format.temperature(102.0, Unit::Celsius),
+            [d(1), d(0), d(2), DEGREE]
+        );
+        assert_eq!(
+            format.temperature(25.0, Unit::Fahrenheit),
+            [d(7), d(7), DEGREE, F]
+        );
+        assert_eq!(
+            format.temperature(-40.0, Unit::Fahrenheit),
+            [MINUS, d(4), d(0), DEGREE]
+        );
+        assert_eq!(format.temperature(1000.0, Unit::Celsius), [MINUS; 4]);
+    }
+
+    #[test]
+    fn clock() {
+        let format = Formatter::new();
+        assert_eq!(format.clock(9, 5, true), [d(0), d(9) | DOT, d(0), d(5)]);
+        assert_eq!(format.clock(23, 59, false), [d(2), d(3), d(5), d(9)]);
+        assert_eq!(format.clock(100, 0, true), [MINUS; 4]);
+        assert_eq!(format.clock(12, 60, true), [MINUS; 4]);
+    }
+
+    #[test]
+    fn countdown() {
+        let format = Formatter::new();
+        assert_eq!(format.countdown(125, true), [d(0), d(2) | DOT, d(0), d(5)]);
+        assert_eq!(format.countdown(5999, false), [d(9), d(9), d(5), d(9)]);
+        assert_eq!(format.countdown(6000, false), [d(0), d(1), d(4), d(0)]);
+        assert_eq!(format.countdown(100 * 3600, false), [MINUS; 4]);
+    }
+}
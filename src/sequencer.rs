@@ -0,0 +1,260 @@
+//! Non-blocking tone sequencer for the Grove Buzzer
+//!
+//! [`Buzzer::beep`] and [`Buzzer::play`] block in a delay for the whole
+//! pattern. [`Sequencer`] instead keeps track of where it is in a pattern of
+//! [`Step`]s and only touches the buzzer when a step starts, so a pattern
+//! plays in the background while the main loop reads sensors or refreshes a
+//! display.
+//!
+//! Drive it either with a timestamp from the main loop:
+//!
+//! ```ignore
+//! let mut sequencer = Sequencer::new();
+//! sequencer.play(&ALARM, true);
+//! loop {
+//!     let now = Instant::now().duration_since_epoch().as_millis() as u32;
+//!     sequencer.poll(&mut buzzer, now)?;
+//!     // ... other work ...
+//! }
+//! ```
+//!
+//! or from a periodic timer interrupt by calling [`Sequencer::tick`] with the
+//! timer period.
+
+#[cfg(target_os = "none")]
+use crate::buzzer::{Buzzer, Error};
+use crate::notes::Note;
+
+/// A tone (or silence) held for a duration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// Tone frequency in Hz, 0 for silence
+    pub frequency_hz: u32,
+    /// How long the step lasts, in milliseconds
+    pub duration_ms: u32,
+}
+
+impl Step {
+    /// A tone of `frequency_hz` lasting `duration_ms`
+    pub const fn tone(frequency_hz: u32, duration_ms: u32) -> Self {
+        Self {
+            frequency_hz,
+            duration_ms,
+        }
+    }
+
+    /// Silence lasting `duration_ms`
+    pub const fn rest(duration_ms: u32) -> Self {
+        Self::tone(0, duration_ms)
+    }
+
+    /// A note (or rest) at `bpm` quarter notes per minute, without a gap
+    pub fn note(note: &Note, bpm: u16) -> Self {
+        Self {
+            frequency_hz: note.frequency().map_or(0, |f| libm::roundf(f) as u32),
+            duration_ms: note.duration_ms(bpm),
+        }
+    }
+}
+
+/// Short confirmation beep
+pub const BEEP: [Step; 2] = [Step::tone(2000, 100), Step::rest(100)];
+
+/// Rising two-tone chime, e.g. for "done" or "connected"
+pub const CHIME: [Step; 3] = [
+    Step::tone(1319, 120),
+    Step::tone(1760, 240),
+    Step::rest(100),
+];
+
+/// Low double buzz for errors
+pub const ERROR: [Step; 4] = [
+    Step::tone(220, 250),
+    Step::rest(100),
+    Step::tone(220, 250),
+    Step::rest(400),
+];
+
+/// Alternating two-tone alarm, meant to be looped
+pub const ALARM: [Step; 2] = [Step::tone(880, 300), Step::tone(660, 300)];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Idle,
+    /// The first step starts at the next poll
+    Starting,
+    Playing,
+    /// The tone was replaced by an empty pattern; silence at the next poll
+    Silencing,
+}
+
+/// Plays a pattern of [`Step`]s without blocking
+#[derive(Clone, Debug)]
+pub struct Sequencer<'p> {
+    pattern: &'p [Step],
+    looping: bool,
+    index: usize,
+    step_started_ms: u32,
+    #[cfg_attr(not(target_os = "none"), allow(dead_code))]
+    clock_ms: u32,
+    state: State,
+}
+
+impl Default for Sequencer<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'p> Sequencer<'p> {
+    /// Create an idle sequencer
+    pub const fn new() -> Self {
+        Self {
+            pattern: &[],
+            looping: false,
+            index: 0,
+            step_started_ms: 0,
+            clock_ms: 0,
+            state: State::Idle,
+        }
+    }
+
+    /// Start `pattern` at the next poll, replacing whatever was playing.
+    ///
+    /// A looping pattern repeats until [`Sequencer::stop`] is called. An
+    /// empty pattern silences the buzzer at the next poll.
+    pub fn play(&mut self, pattern: &'p [Step], looping: bool) {
+        self.pattern = pattern;
+        self.looping = looping;
+        self.index = 0;
+        self.state = if !pattern.is_empty() {
+            State::Starting
+        } else if self.state == State::Idle {
+            State::Idle
+        } else {
+            // A pattern that had not started yet may replace a sounding one
+            State::Silencing
+        };
+    }
+
+    /// Whether a pattern is playing or about to start
+    pub fn is_playing(&self) -> bool {
+        self.state != State::Idle
+    }
+
+    /// Advance to `now_ms`, returning the frequency to switch to (0 = silence).
+    ///
+    /// Returns `None` when the buzzer should keep its current tone. Steps that
+    /// were missed because of a late poll are skipped, while the pattern keeps
+    /// its overall timing.
+    pub fn advance(&mut self, now_ms: u32) -> Option<u32> {
+        match self.state {
+            State::Idle => None,
+            State::Silencing => {
+                self.state = State::Idle;
+                Some(0)
+            }
+            State::Starting => {
+                self.state = State::Playing;
+                self.step_started_ms = now_ms;
+                Some(self.pattern[0].frequency_hz)
+            }
+            State::Playing => {
+                let mut changed = false;
+
+                loop {
+                    let duration = self.pattern[self.index].duration_ms;
+                    if now_ms.wrapping_sub(self.step_started_ms) < duration {
+                        break;
+                    }
+
+                    self.step_started_ms = self.step_started_ms.wrapping_add(duration);
+                    self.index += 1;
+                    changed = true;
+
+                    if self.index == self.pattern.len() {
+                        if !self.looping || self.pattern.iter().all(|s| s.duration_ms == 0) {
+                            self.state = State::Idle;
+                            return Some(0);
+                        }
+                        self.index = 0;
+                    }
+                }
+
+                changed.then(|| self.pattern[self.index].frequency_hz)
+            }
+        }
+    }
+
+    /// Advance to `now_ms` and update the buzzer; returns whether still playing
+    #[cfg(target_os = "none")]
+    pub fn poll(&mut self, buzzer: &mut Buzzer, now_ms: u32) -> Result<bool, Error> {
+        if let Some(frequency_hz) = self.advance(now_ms) {
+            buzzer.tone(frequency_hz)?;
+        }
+        Ok(self.is_playing())
+    }
+
+    /// Advance by `elapsed_ms` from a periodic timer; returns whether still playing
+    #[cfg(target_os = "none")]
+    pub fn tick(&mut self, buzzer: &mut Buzzer, elapsed_ms: u32) -> Result<bool, Error> {
+        self.clock_ms = self.clock_ms.wrapping_add(elapsed_ms);
+        self.poll(buzzer, self.clock_ms)
+    }
+
+    /// Stop the pattern and silence the buzzer
+    #[cfg(target_os = "none")]
+    pub fn stop(&mut self, buzzer: &mut Buzzer) -> Result<(), Error> {
+        self.state = State::Idle;
+        buzzer.silence()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plays_steps_then_silences() {
+        let mut sequencer = Sequencer::new();
+        sequencer.play(&CHIME, false);
+        assert_eq!(sequencer.advance(1000), Some(1319));
+        assert_eq!(sequencer.advance(1119), None);
+        assert_eq!(sequencer.advance(1120), Some(1760));
+        assert_eq!(sequencer.advance(1360), Some(0));
+        assert_eq!(sequencer.advance(1460), Some(0));
+        assert!(!sequencer.is_playing());
+        assert_eq!(sequencer.advance(2000), None);
+    }
+
+    #[test]
+    fn looping_keeps_timing() {
+        let mut sequencer = Sequencer::new();
+        sequencer.play(&ALARM, true);
+        assert_eq!(sequencer.advance(0), Some(880));
+        // A late poll skips the missed step
+        assert_eq!(sequencer.advance(650), Some(880));
+        assert_eq!(sequencer.advance(900), Some(660));
+        assert!(sequencer.is_playing());
+    }
+
+    #[test]
+    fn empty_pattern_silences_a_sounding_tone() {
+        let mut sequencer = Sequencer::new();
+        sequencer.play(&ALARM, true);
+        assert_eq!(sequencer.advance(0), Some(880));
+        sequencer.play(&[], false);
+        assert!(sequencer.is_playing());
+        assert_eq!(sequencer.advance(10), Some(0));
+        assert!(!sequencer.is_playing());
+        assert_eq!(sequencer.advance(20), None);
+    }
+
+    #[test]
+    fn empty_pattern_when_idle_stays_silent() {
+        let mut sequencer = Sequencer::new();
+        sequencer.play(&[], false);
+        assert!(!sequencer.is_playing());
+        assert_eq!(sequencer.advance(0), None);
+    }
+}
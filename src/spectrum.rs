@@ -0,0 +1,170 @@
+//! Spectrum analyzer bands and renderers
+//!
+//! [`Spectrum`] groups FFT bins from [`crate::fft`] into logarithmically
+//! spaced bands, converts them to a 0.0-1.0 level on a decibel scale and
+//! lets bars fall back slowly, like a classic hi-fi analyzer. The levels can
+//! then be drawn as bar graphs on an SSD1306 (any `embedded-graphics`
+//! `BinaryColor` target) or mapped onto the LEDs of the RGB stick.
+
+use embedded_graphics::{
+    pixelcolor::BinaryColor,
+    prelude::*,
+    primitives::{PrimitiveStyle, Rectangle},
+};
+use smart_leds::{
+    hsv::{hsv2rgb, Hsv},
+    RGB8,
+};
+
+/// Band levels of a spectrum analyzer with `B` bands
+pub struct Spectrum<const B: usize> {
+    levels: [f32; B],
+    peaks: [f32; B],
+    floor_db: f32,
+    range_db: f32,
+    decay: f32,
+}
+
+impl<const B: usize> Spectrum<B> {
+    /// Create an analyzer showing `range_db` decibels above `floor_db`.
+    ///
+    /// Amplitudes are in the FFT input unit, so with calibrated ADC samples
+    /// 0 dB corresponds to 1 mV.
+    pub fn new(floor_db: f32, range_db: f32) -> Self {
+        Self {
+            levels: [0.0; B],
+            peaks: [0.0; B],
+            floor_db,
+            range_db,
+            decay: 0.05,
+        }
+    }
+
+    /// Set how much a bar may fall per update (0.0-1.0 of full scale)
+    pub fn with_decay(mut self, decay: f32) -> Self {
+        self.decay = decay;
+        self
+    }
+
+    /// Update the band levels from an amplitude spectrum (FFT bins `0..N/2`).
+    ///
+    /// Bin 0 (DC) is skipped. Bands are spaced logarithmically so low
+    /// frequencies get as much room as high ones.
+    pub fn update(&mut self, bins: &[f32]) {
+        let usable = bins.len().saturating_sub(1);
+        if usable == 0 {
+            return;
+        }
+
+        let mut start = 1;
+        for band in 0..B {
+            let end = band_edge(band + 1, B, usable)
+                .max(start + 1)
+                .min(bins.len());
+            let amplitude = bins[start.min(end - 1)..end]
+                .iter()
+                .copied()
+                .fold(0.0f32, f32::max);
+            start = end;
+
+            let db = 20.0 * libm::log10f(amplitude.max(1e-6));
+            let level = ((db - self.floor_db) / self.range_db).clamp(0.0, 1.0);
+
+            // Rise instantly, fall slowly
+            self.levels[band] = level.max(self.levels[band] - self.decay);
+            self.peaks[band] = self.levels[band].max(self.peaks[band] - self.decay / 4.0);
+        }
+    }
+
+    /// Current level of each band, 0.0-1.0
+    pub fn levels(&self) -> &[f32; B] {
+        &self.levels
+    }
+
+    /// Slowly falling peak marker of each band, 0.0-1.0
+    pub fn peaks(&self) -> &[f32; B] {
+        &self.peaks
+    }
+
+    /// Draw the bands as vertical bars with peak markers inside `area`
+    pub fn draw<D>(&self, target: &mut D, area: Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = BinaryColor>,
+    {
+        let slot = area.size.width / B as u32;
+        let bar_width = slot.saturating_sub(1).max(1);
+        let height = area.size.height as f32;
+        let bottom = area.top_left.y + area.size.height as i32;
+
+        for (band, (&level, &peak)) in self.levels.iter().zip(self.peaks.iter()).enumerate() {
+            let x = area.top_left.x + (band as u32 * slot) as i32;
+
+            let bar = (level * height) as u32;
+            if bar > 0 {
+                Rectangle::new(
+                    Point::new(x, bottom - bar as i32),
+                    Size::new(bar_width, bar),
+                )
+                .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+                .draw(target)?;
+            }
+
+            let peak_y = bottom - 1 - (peak * (height - 1.0)) as i32;
+            Rectangle::new(Point::new(x, peak_y), Size::new(bar_width, 1))
+                .into_styled(PrimitiveStyle::with_fill(BinaryColor::On))
+                .draw(target)?;
+        }
+
+        Ok(())
+    }
+
+    /// Map the bands onto a LED strip.
+    ///
+    /// With fewer LEDs than bands each LED shows the average of its bands,
+    /// with more LEDs a band spreads over several. LEDs are colored from red
+    /// for bass to violet for treble, with the level as brightness up to
+    /// `max_brightness`.
+    pub fn render_leds(&self, leds: &mut [RGB8], max_brightness: u8) {
+        let count = leds.len();
+        for (i, led) in leds.iter_mut().enumerate() {
+            let first = (i * B / count).min(B - 1);
+            let last = ((i + 1) * B / count).clamp(first + 1, B);
+            let bands = &self.levels[first..last];
+            let level = bands.iter().sum::<f32>() / bands.len() as f32;
+            *led = hsv2rgb(Hsv {
+                hue: (i * 200 / count) as u8,
+                sat: 255,
+                val: (level * max_brightness as f32) as u8,
+            });
+        }
+    }
+}
+
+/// Upper bin edge (exclusive, offset by the skipped DC bin) of band `band`
+fn band_edge(band: usize, bands: usize, bins: usize) -> usize {
+    let exponent = band as f32 / bands as f32;
+    1 + libm::roundf(libm::powf(bins as f32, exponent)) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brightness(leds: &[RGB8]) -> Vec<u8> {
+        leds.iter().map(|led| led.r.max(led.g).max(led.b)).collect()
+    }
+
+    #[test]
+    fn leds_average_or_stretch_bands() {
+        let mut spectrum = Spectrum::<4>::new(0.0, 40.0);
+        spectrum.levels = [0.2, 0.6, 1.0, 0.0];
+
+        let mut leds = [RGB8::default(); 2];
+        spectrum.render_leds(&mut leds, 100);
+        assert_eq!(brightness(&leds), [40, 50]);
+
+        let mut leds = [RGB8::default(); 8];
+        spectrum.render_leds(&mut leds, 100);
+        assert_eq!(brightness(&leds), [20, 20, 60, 60, 100, 100, 0, 0]);
+    }
+}
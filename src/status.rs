@@ -0,0 +1,178 @@
+//! Status LED pattern engine
+//!
+//! A single LED can tell a lot about the firmware state without a serial
+//! console: a slow heartbeat while all is well, a fast blink on errors, or a
+//! blink code counting out which error happened. [`Pattern`] describes these
+//! as a brightness over time and [`StatusLed`] plays the selected pattern on
+//! any on/off [`Keyer`], such as the onboard LED on GPIO15:
+//!
+//! ```ignore
+//! let led = Output::new(peripherals.GPIO15, Level::High, OutputConfig::default());
+//! let mut status = StatusLed::new(ActiveLow(led));
+//! status.set_pattern(Pattern::Heartbeat);
+//! loop {
+//!     status.update(now_ms())?;
+//!     // ...
+//! }
+//! ```
+//!
+//! Dimmed levels (breathing) are produced on a plain GPIO by switching the
+//! LED on and off in proportion to the brightness (first-order delta-sigma
+//! modulation), so [`StatusLed::update`] should be called about every
+//! millisecond while such a pattern is selected.
+
+use core::f32::consts::PI;
+
+use crate::{
+    dimmer::cie1931,
+    morse::{Encoder, Keyer, Timing},
+};
+
+/// Blink timing of one element of a blink code
+const CODE_ON_MS: u32 = 200;
+const CODE_OFF_MS: u32 = 300;
+/// Pause after a blink code before it repeats
+const CODE_PAUSE_MS: u32 = 1500;
+
+/// Morse speed of the SOS pattern
+const SOS_WPM: u32 = 12;
+
+/// A named LED pattern, repeating forever
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pattern {
+    /// Always off
+    Off,
+    /// Always on
+    On,
+    /// Regular blink
+    Blink { on_ms: u32, off_ms: u32 },
+    /// Double pulse once per 1.2 s, like a heartbeat: all is well
+    Heartbeat,
+    /// Fast 5 Hz blink: something is wrong
+    ErrorBlink,
+    /// `n` blinks followed by a pause, to tell errors apart
+    Code(u8),
+    /// Smooth fade in and out over `period_ms`
+    Breathing { period_ms: u32 },
+    /// "SOS" in Morse code
+    Sos,
+}
+
+impl Pattern {
+    /// Length of one repetition in milliseconds
+    pub fn period_ms(&self) -> u32 {
+        match *self {
+            Pattern::Off | Pattern::On => 1,
+            Pattern::Blink { on_ms, off_ms } => (on_ms + off_ms).max(1),
+            Pattern::Heartbeat => 1200,
+            Pattern::ErrorBlink => 200,
+            Pattern::Code(n) => n as u32 * (CODE_ON_MS + CODE_OFF_MS) + CODE_PAUSE_MS,
+            Pattern::Breathing { period_ms } => period_ms.max(1),
+            Pattern::Sos => {
+                let timing = Timing::new(SOS_WPM);
+                let message: u32 = Encoder::new("SOS", timing)
+                    .map(|event| event.duration_ms)
+                    .sum();
+                message + timing.word_gap_ms
+            }
+        }
+    }
+
+    /// Brightness (0-255) at `elapsed_ms` since the pattern started
+    pub fn level(&self, elapsed_ms: u32) -> u8 {
+        let t = elapsed_ms % self.period_ms();
+        let on = match *self {
+            Pattern::Off => false,
+            Pattern::On => true,
+            Pattern::Blink { on_ms, .. } => t < on_ms,
+            Pattern::Heartbeat => t < 100 || (250..350).contains(&t),
+            Pattern::ErrorBlink => t < 100,
+            Pattern::Code(n) => {
+                let blink = CODE_ON_MS + CODE_OFF_MS;
+                t < n as u32 * blink && t % blink < CODE_ON_MS
+            }
+            Pattern::Breathing { period_ms } => {
+                // Breathe in perceived brightness, then convert to duty
+                let phase = 2.0 * PI * t as f32 / period_ms.max(1) as f32;
+                let brightness = (127.5 * (1.0 - libm::cosf(phase))) as u8;
+                return libm::roundf(cie1931(brightness) * 255.0) as u8;
+            }
+            Pattern::Sos => {
+                let mut start = 0;
+                Encoder::new("SOS", Timing::new(SOS_WPM))
+                    .find(|event| {
+                        start += event.duration_ms;
+                        t < start
+                    })
+                    .is_some_and(|event| event.on)
+            }
+        };
+
+        if on {
+            u8::MAX
+        } else {
+            0
+        }
+    }
+}
+
+/// Plays a [`Pattern`] on an on/off output
+pub struct StatusLed<K> {
+    keyer: K,
+    pattern: Pattern,
+    /// When the pattern started; set on the first update after a change
+    started_ms: Option<u32>,
+    /// Delta-sigma accumulator for dimmed levels
+    error: u16,
+}
+
+impl<K: Keyer> StatusLed<K> {
+    /// Create a status LED that is off
+    pub fn new(keyer: K) -> Self {
+        Self {
+            keyer,
+            pattern: Pattern::Off,
+            started_ms: None,
+            error: 0,
+        }
+    }
+
+    /// Select a pattern; selecting the current one again does not restart it
+    pub fn set_pattern(&mut self, pattern: Pattern) {
+        if pattern != self.pattern {
+            self.pattern = pattern;
+            self.started_ms = None;
+        }
+    }
+
+    /// The selected pattern
+    pub fn pattern(&self) -> Pattern {
+        self.pattern
+    }
+
+    /// Update the LED for `now_ms`
+    pub fn update(&mut self, now_ms: u32) -> Result<(), K::Error> {
+        let started = *self.started_ms.get_or_insert(now_ms);
+        let level = self.pattern.level(now_ms.wrapping_sub(started));
+
+        let on = match level {
+            0 => false,
+            u8::MAX => true,
+            _ => {
+                self.error += level as u16;
+                let on = self.error >= u8::MAX as u16;
+                if on {
+                    self.error -= u8::MAX as u16;
+                }
+                on
+            }
+        };
+
+        self.keyer.key(on)
+    }
+
+    /// Release the output
+    pub fn release(self) -> K {
+        self.keyer
+    }
+}
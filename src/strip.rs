@@ -0,0 +1,209 @@
+//! LED strip configuration: length, color order, RGBW and matrix layouts
+//!
+//! [`Strip`] holds a frame for up to `MAX` LEDs, of which a runtime number
+//! are used, so one firmware can drive a single Grove RGB LED Stick or
+//! several chained ones. Effects render into [`Strip::pixels_mut`] as plain
+//! RGB; the strip then produces the bytes in the order the LEDs expect:
+//!
+//! - [`ColorOrder`] for RGB LEDs (WS2812/WS2813 use GRB, some clones RGB or
+//!   BRG), written with [`Strip::wire`] to an adapter created with
+//!   `SmartLedsAdapter::new_with_color` for `RGB8`,
+//! - RGBW LEDs (SK6812) with [`Strip::wire_rgbw`], which moves the white
+//!   part of each color onto the white channel, for `RGBA<u8>` adapters sized
+//!   with `smart_led_buffer!(MAX; RGBW)`.
+//!
+//! LED matrices are strips folded into rows; [`Strip::matrix`] maps x/y
+//! coordinates onto the strip for both common [`Wiring`]s:
+//!
+//! ```ignore
+//! let mut strip = Strip::<64>::matrix(8, 8, Wiring::Serpentine)?;
+//! strip.set(3, 4, RGB8::new(0, 0, 64));
+//! leds.write(strip.wire())?;
+//! ```
+
+use smart_leds::{RGB8, RGBA};
+
+/// Strip configuration errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// More LEDs than the strip has room for
+    TooLong,
+}
+
+/// Order in which the LEDs expect the color channels
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorOrder {
+    Rgb,
+    /// WS2812, WS2813 and SK6812
+    #[default]
+    Grb,
+    Brg,
+    Rbg,
+    Gbr,
+    Bgr,
+}
+
+impl ColorOrder {
+    /// Rearrange `color` into wire order: the fields of the result hold the
+    /// first, second and third byte sent
+    pub fn arrange(self, color: RGB8) -> RGB8 {
+        let RGB8 { r, g, b } = color;
+        match self {
+            ColorOrder::Rgb => RGB8::new(r, g, b),
+            ColorOrder::Grb => RGB8::new(g, r, b),
+            ColorOrder::Brg => RGB8::new(b, r, g),
+            ColorOrder::Rbg => RGB8::new(r, b, g),
+            ColorOrder::Gbr => RGB8::new(g, b, r),
+            ColorOrder::Bgr => RGB8::new(b, g, r),
+        }
+    }
+
+    /// Rearrange `color` for an RGBW LED, moving the white part of the color
+    /// (the smallest channel) onto the white channel, which is sent last
+    pub fn arrange_rgbw(self, color: RGB8) -> RGBA<u8> {
+        let white = color.r.min(color.g).min(color.b);
+        let rgb = self.arrange(RGB8::new(color.r - white, color.g - white, color.b - white));
+        RGBA::new(rgb.r, rgb.g, rgb.b, white)
+    }
+}
+
+/// How the rows of a LED matrix are chained
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wiring {
+    /// Every row runs left to right
+    Progressive,
+    /// Rows alternate direction, zig-zagging through the matrix
+    Serpentine,
+}
+
+/// A frame for a LED strip or matrix of up to `MAX` LEDs
+pub struct Strip<const MAX: usize> {
+    pixels: [RGB8; MAX],
+    len: usize,
+    order: ColorOrder,
+    /// Matrix width and wiring, or `None` for a plain strip
+    matrix: Option<(usize, Wiring)>,
+}
+
+impl<const MAX: usize> Strip<MAX> {
+    /// Create a strip of `len` LEDs with GRB color order
+    pub fn new(len: usize) -> Result<Self, Error> {
+        if len > MAX {
+            return Err(Error::TooLong);
+        }
+        Ok(Self {
+            pixels: [RGB8::default(); MAX],
+            len,
+            order: ColorOrder::default(),
+            matrix: None,
+        })
+    }
+
+    /// Create a matrix of `width` x `height` LEDs, chained row by row from
+    /// the top left
+    pub fn matrix(width: usize, height: usize, wiring: Wiring) -> Result<Self, Error> {
+        let mut strip = Self::new(width.checked_mul(height).ok_or(Error::TooLong)?)?;
+        strip.matrix = Some((width, wiring));
+        Ok(strip)
+    }
+
+    /// Set the color order of the LEDs
+    pub fn with_order(mut self, order: ColorOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Number of LEDs in use
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no LEDs are in use
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Change the number of LEDs in use, e.g. after chaining another stick.
+    /// A matrix becomes a plain strip.
+    pub fn set_len(&mut self, len: usize) -> Result<(), Error> {
+        if len > MAX {
+            return Err(Error::TooLong);
+        }
+        self.pixels[len.min(self.len)..].fill(RGB8::default());
+        self.len = len;
+        self.matrix = None;
+        Ok(())
+    }
+
+    /// Matrix width, or the length for a plain strip
+    pub fn width(&self) -> usize {
+        self.matrix.map_or(self.len, |(width, _)| width)
+    }
+
+    /// Matrix height, or 1 for a plain strip
+    pub fn height(&self) -> usize {
+        match self.matrix {
+            Some((width, _)) if width > 0 => self.len / width,
+            Some(_) => 0,
+            None => 1,
+        }
+    }
+
+    /// Colors of the LEDs in strip order
+    pub fn pixels(&self) -> &[RGB8] {
+        &self.pixels[..self.len]
+    }
+
+    /// Colors of the LEDs in strip order, for effects to render into
+    pub fn pixels_mut(&mut self) -> &mut [RGB8] {
+        &mut self.pixels[..self.len]
+    }
+
+    /// Strip index of the LED at column `x` and row `y`
+    pub fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if x >= self.width() || y >= self.height() {
+            return None;
+        }
+        let column = match self.matrix {
+            Some((width, Wiring::Serpentine)) if y % 2 == 1 => width - 1 - x,
+            _ => x,
+        };
+        Some(y * self.width() + column)
+    }
+
+    /// Color of the LED at `x`, `y`
+    pub fn get(&self, x: usize, y: usize) -> Option<RGB8> {
+        self.index(x, y).map(|i| self.pixels[i])
+    }
+
+    /// Set the LED at `x`, `y`; coordinates outside the matrix are ignored
+    pub fn set(&mut self, x: usize, y: usize, color: RGB8) {
+        if let Some(i) = self.index(x, y) {
+            self.pixels[i] = color;
+        }
+    }
+
+    /// Set all LEDs to `color`
+    pub fn fill(&mut self, color: RGB8) {
+        self.pixels_mut().fill(color);
+    }
+
+    /// Turn all LEDs off
+    pub fn clear(&mut self) {
+        self.fill(RGB8::default());
+    }
+
+    /// Frame in wire order for RGB LEDs
+    pub fn wire(&self) -> impl Iterator<Item = RGB8> + '_ {
+        self.pixels()
+            .iter()
+            .map(move |&color| self.order.arrange(color))
+    }
+
+    /// Frame in wire order for RGBW LEDs
+    pub fn wire_rgbw(&self) -> impl Iterator<Item = RGBA<u8>> + '_ {
+        self.pixels()
+            .iter()
+            .map(move |&color| self.order.arrange_rgbw(color))
+    }
+}
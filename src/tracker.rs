@@ -0,0 +1,294 @@
+//! Tracker-style patterns with arpeggiated chords
+//!
+//! A piezo can only sound one tone at a time. Chiptune music works around
+//! that by cycling rapidly through the notes of a chord (an arpeggio), which
+//! the ear hears as a chord. [`Tracker`] does the same for a small pattern
+//! written like a music tracker: one row per line (or `|`), one
+//! whitespace-separated cell per channel.
+//!
+//! ```text
+//! C-4 E-4 G-4
+//! --- --- ---
+//! === F-4 A-4
+//! ```
+//!
+//! A cell is a note (`C-4`, `C#4`), `---` or `...` to keep the channel as it
+//! is, or `===` / `OFF` to stop it. Rows with one sounding channel play that
+//! note, rows with several arpeggiate them and rows where no channel sounds
+//! are silent. Blank lines only separate parts of the pattern and take no
+//! time.
+//!
+//! [`Tracker::steps`] turns the pattern into a schedule of [`Step`]s that can
+//! be played with [`Buzzer::play_tracker`](crate::buzzer::Buzzer::play_tracker)
+//! or compiled into a buffer for the non-blocking
+//! [`Sequencer`](crate::sequencer::Sequencer).
+
+use crate::{
+    notes::{Pitch, MAX_OCTAVE},
+    sequencer::Step,
+};
+
+/// Default time each chord note sounds while arpeggiating (50 Hz, as in
+/// classic chiptunes)
+pub const DEFAULT_ARPEGGIO_MS: u32 = 20;
+
+/// Errors while reading a pattern
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// A cell is not a note, `---`/`...` or `===`/`OFF`
+    InvalidCell,
+    /// A row has more cells than the pattern has channels
+    TooManyCells,
+    /// The buffer passed to [`Tracker::compile`] is too small
+    BufferFull,
+}
+
+/// Contents of one cell of a row
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cell {
+    /// Keep the channel as it is
+    Hold,
+    /// Start a note on the channel
+    Note(Pitch, u8),
+    /// Silence the channel
+    Off,
+}
+
+impl Cell {
+    /// Parse a cell such as `C-4`, `C#4`, `---` or `===`
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        match text {
+            "---" | "..." => return Ok(Cell::Hold),
+            "===" | "OFF" | "off" => return Ok(Cell::Off),
+            _ => {}
+        }
+
+        let &[letter, accidental, octave] = text.as_bytes() else {
+            return Err(Error::InvalidCell);
+        };
+
+        let sharp = match accidental {
+            b'-' => false,
+            b'#' => true,
+            _ => return Err(Error::InvalidCell),
+        };
+
+        let octave = octave.wrapping_sub(b'0');
+        if octave > MAX_OCTAVE {
+            return Err(Error::InvalidCell);
+        }
+
+        let pitch = match (letter.to_ascii_uppercase(), sharp) {
+            (b'C', false) => Pitch::C,
+            (b'C', true) => Pitch::CSharp,
+            (b'D', false) => Pitch::D,
+            (b'D', true) => Pitch::DSharp,
+            (b'E', false) => Pitch::E,
+            (b'F', false) => Pitch::F,
+            (b'F', true) => Pitch::FSharp,
+            (b'G', false) => Pitch::G,
+            (b'G', true) => Pitch::GSharp,
+            (b'A', false) => Pitch::A,
+            (b'A', true) => Pitch::ASharp,
+            (b'B', false) => Pitch::B,
+            _ => return Err(Error::InvalidCell),
+        };
+
+        Ok(Cell::Note(pitch, octave))
+    }
+}
+
+/// A pattern with `C` channels
+#[derive(Clone, Copy, Debug)]
+pub struct Tracker<'a, const C: usize> {
+    rows: &'a str,
+    row_ms: u32,
+    arpeggio_ms: u32,
+}
+
+impl<'a, const C: usize> Tracker<'a, C> {
+    /// A pattern from `rows` where every row lasts `row_ms`
+    pub fn new(rows: &'a str, row_ms: u32) -> Self {
+        Self {
+            rows,
+            row_ms,
+            arpeggio_ms: DEFAULT_ARPEGGIO_MS,
+        }
+    }
+
+    /// Set how long each chord note sounds while arpeggiating
+    pub fn with_arpeggio(mut self, arpeggio_ms: u32) -> Self {
+        self.arpeggio_ms = arpeggio_ms.max(1);
+        self
+    }
+
+    /// Iterate over the tone schedule of the pattern
+    pub fn steps(&self) -> Steps<'a, C> {
+        Steps {
+            rows: self.rows.split(['\n', '|']),
+            row_ms: self.row_ms,
+            arpeggio_ms: self.arpeggio_ms,
+            channels: [None; C],
+            remaining_ms: 0,
+            phase: 0,
+        }
+    }
+
+    /// Write the tone schedule into `buffer`, e.g. for a
+    /// [`Sequencer`](crate::sequencer::Sequencer)
+    pub fn compile<'s>(&self, buffer: &'s mut [Step]) -> Result<&'s [Step], Error> {
+        let mut len = 0;
+        for step in self.steps() {
+            let slot = buffer.get_mut(len).ok_or(Error::BufferFull)?;
+            *slot = step?;
+            len += 1;
+        }
+        Ok(&buffer[..len])
+    }
+}
+
+/// Iterator over the tone schedule of a [`Tracker`] pattern
+#[derive(Clone, Debug)]
+pub struct Steps<'a, const C: usize> {
+    rows: core::str::Split<'a, [char; 2]>,
+    row_ms: u32,
+    arpeggio_ms: u32,
+    /// Frequency sounding on each channel
+    channels: [Option<u32>; C],
+    /// Time left in the current row
+    remaining_ms: u32,
+    /// Position in the arpeggio, kept across rows so chords cycle smoothly
+    phase: usize,
+}
+
+impl<const C: usize> Steps<'_, C> {
+    fn read_row(&mut self, row: &str) -> Result<(), Error> {
+        for (channel, text) in row.split_whitespace().enumerate() {
+            let slot = self.channels.get_mut(channel).ok_or(Error::TooManyCells)?;
+            match Cell::parse(text)? {
+                Cell::Hold => {}
+                Cell::Note(pitch, octave) => {
+                    *slot = Some(libm::roundf(pitch.frequency(octave)) as u32)
+                }
+                Cell::Off => *slot = None,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const C: usize> Iterator for Steps<'_, C> {
+    type Item = Result<Step, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.remaining_ms == 0 {
+            let row = self.rows.next()?.trim();
+            if row.is_empty() {
+                continue;
+            }
+            if let Err(e) = self.read_row(row) {
+                return Some(Err(e));
+            }
+            self.remaining_ms = self.row_ms;
+        }
+
+        let mut sounding = self.channels.iter().flatten();
+        let count = sounding.clone().count();
+
+        let step = if count <= 1 {
+            Step::tone(sounding.next().copied().unwrap_or(0), self.remaining_ms)
+        } else {
+            let frequency = sounding.nth(self.phase % count).copied().unwrap_or(0);
+            self.phase = self.phase.wrapping_add(1);
+            Step::tone(frequency, self.arpeggio_ms.min(self.remaining_ms))
+        };
+
+        self.remaining_ms -= step.duration_ms;
+        Some(Ok(step))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const C4: u32 = 262;
+    const E4: u32 = 330;
+    const G4: u32 = 392;
+
+    fn steps<const C: usize>(tracker: Tracker<'_, C>) -> Result<Vec<Step>, Error> {
+        tracker.steps().collect()
+    }
+
+    #[test]
+    fn cells() {
+        assert_eq!(Cell::parse("C-4"), Ok(Cell::Note(Pitch::C, 4)));
+        assert_eq!(Cell::parse("f#3"), Ok(Cell::Note(Pitch::FSharp, 3)));
+        assert_eq!(Cell::parse("..."), Ok(Cell::Hold));
+        assert_eq!(Cell::parse("OFF"), Ok(Cell::Off));
+        assert_eq!(Cell::parse("E#4"), Err(Error::InvalidCell));
+        assert_eq!(Cell::parse("C-9"), Err(Error::InvalidCell));
+        assert_eq!(Cell::parse("C4"), Err(Error::InvalidCell));
+    }
+
+    #[test]
+    fn single_note_lasts_the_row() {
+        let tracker = Tracker::<2>::new("C-4 ---", 100);
+        assert_eq!(steps(tracker).unwrap(), [Step::tone(C4, 100)]);
+    }
+
+    #[test]
+    fn arpeggio_cycles_across_rows() {
+        let tracker = Tracker::<3>::new("C-4 E-4 G-4|--- --- ---", 50).with_arpeggio(20);
+        assert_eq!(
+            steps(tracker).unwrap(),
+            [
+                Step::tone(C4, 20),
+                Step::tone(E4, 20),
+                Step::tone(G4, 10),
+                Step::tone(C4, 20),
+                Step::tone(E4, 20),
+                Step::tone(G4, 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn hold_keeps_and_off_stops_a_channel() {
+        let tracker = Tracker::<2>::new("C-4 E-4\n=== ---\n--- ===", 20).with_arpeggio(10);
+        assert_eq!(
+            steps(tracker).unwrap(),
+            [
+                Step::tone(C4, 10),
+                Step::tone(E4, 10),
+                Step::tone(E4, 20),
+                Step::rest(20),
+            ]
+        );
+    }
+
+    #[test]
+    fn blank_lines_take_no_time() {
+        let tracker = Tracker::<1>::new("C-4\n\n  \n===", 100);
+        assert_eq!(
+            steps(tracker).unwrap(),
+            [Step::tone(C4, 100), Step::rest(100)]
+        );
+    }
+
+    #[test]
+    fn compile_errors() {
+        let mut buffer = [Step::rest(0); 2];
+        let tracker = Tracker::<1>::new("C-4|E-4", 100);
+        assert_eq!(
+            tracker.compile(&mut buffer),
+            Ok(&[Step::tone(C4, 100), Step::tone(E4, 100)][..])
+        );
+        let tracker = Tracker::<1>::new("C-4|E-4|G-4", 100);
+        assert_eq!(tracker.compile(&mut buffer), Err(Error::BufferFull));
+        let tracker = Tracker::<1>::new("C-4 E-4", 100);
+        assert_eq!(tracker.compile(&mut buffer), Err(Error::TooManyCells));
+        let tracker = Tracker::<1>::new("C-4|X-4", 100);
+        assert_eq!(tracker.compile(&mut buffer), Err(Error::InvalidCell));
+    }
+}
@@ -0,0 +1,311 @@
+//! Non-blocking WS2812/WS2813 output over RMT
+//!
+//! `SmartLedsAdapter::write` from `esp-hal-smartled` waits while RMT clocks
+//! out every bit of a frame, because the channel RAM only holds a few LEDs
+//! and has to be refilled as it drains. [`Ws2812`] instead refills it from
+//! the RMT interrupt, so [`Ws2812::try_write`] returns as soon as the frame
+//! is encoded and the next frame can be computed while this one is sent:
+//! the application renders into its color frame while the driver's pulse
+//! buffer is on the wire.
+//!
+//! The driver lives in a `critical_section::Mutex` shared with the RMT
+//! interrupt handler, and its pulse buffer in a [`StaticBuffer`]:
+//!
+//! ```ignore
+//! static BUFFER: StaticBuffer<{ buffer_size(15) }> = StaticBuffer::new();
+//! static LEDS: Mutex<RefCell<Option<Ws2812<{ buffer_size(15) }>>>> = Mutex::new(RefCell::new(None));
+//!
+//! #[handler]
+//! fn rmt_handler() {
+//!     critical_section::with(|cs| {
+//!         if let Some(leds) = LEDS.borrow_ref_mut(cs).as_mut() {
+//!             leds.on_interrupt();
+//!         }
+//!     });
+//! }
+//! ```
+
+use core::{
+    cell::UnsafeCell,
+    ptr::NonNull,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use esp_hal::{
+    clock::Clocks,
+    gpio::{interconnect::PeripheralOutput, Level},
+    peripherals::RMT,
+    rmt::{
+        self, Channel, ChannelCreator, PulseCode, SingleShotTxTransaction, Tx, TxChannelConfig,
+        TxChannelCreator,
+    },
+    Blocking,
+};
+
+// Bit timing shared by WS2812, WS2813 and SK6812, in nanoseconds
+const PERIOD_NS: u32 = 1250;
+const T0H_NS: u32 = 400;
+const T1H_NS: u32 = 850;
+
+// Channel RAM blocks, so the interrupt has time to refill half of it
+const MEMSIZE: u8 = 2;
+
+/// Pulse buffer size for `leds` RGB LEDs
+pub const fn buffer_size(leds: usize) -> usize {
+    leds * 3 * 8 + 1
+}
+
+/// Pulse buffer size for `leds` RGBW LEDs
+pub const fn buffer_size_rgbw(leds: usize) -> usize {
+    leds * 4 * 8 + 1
+}
+
+/// Errors from the LED driver
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Error {
+    /// The frame has more LEDs than the pulse buffer has room for
+    BufferSizeExceeded,
+    /// An earlier transmission could not be started and took the channel
+    /// with it; no more frames can be sent
+    ChannelLost,
+    /// RMT driver error
+    Rmt(rmt::Error),
+}
+
+impl From<rmt::Error> for Error {
+    fn from(e: rmt::Error) -> Self {
+        Error::Rmt(e)
+    }
+}
+
+/// A pulse buffer in a `static`, handed out once as `&'static mut`
+pub struct StaticBuffer<const P: usize> {
+    taken: AtomicBool,
+    pulses: UnsafeCell<[PulseCode; P]>,
+}
+
+// SAFETY: the pulses are only reachable through the one reference `take`
+// hands out
+unsafe impl<const P: usize> Sync for StaticBuffer<P> {}
+
+impl<const P: usize> StaticBuffer<P> {
+    /// Create an unused buffer
+    pub const fn new() -> Self {
+        Self {
+            taken: AtomicBool::new(false),
+            pulses: UnsafeCell::new([PulseCode::end_marker(); P]),
+        }
+    }
+
+    /// Take the buffer, or `None` if it was taken before
+    #[allow(clippy::mut_from_ref)]
+    pub fn take(&'static self) -> Option<&'static mut [PulseCode; P]> {
+        if self.taken.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+        // SAFETY: `taken` guarantees this is the only reference
+        Some(unsafe { &mut *self.pulses.get() })
+    }
+}
+
+impl<const P: usize> Default for StaticBuffer<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum State {
+    Idle(Channel<'static, Blocking, Tx>),
+    Sending(SingleShotTxTransaction<'static, 'static, PulseCode>),
+    Lost,
+}
+
+/// Interrupt-driven WS2812 driver with a pulse buffer of `P` codes (see
+/// [`buffer_size`])
+pub struct Ws2812<const P: usize> {
+    state: State,
+    /// The `&'static mut` pulse buffer, shared with a running transmission
+    buffer: NonNull<[PulseCode; P]>,
+    channel: u8,
+    zero: PulseCode,
+    one: PulseCode,
+    frames: u32,
+    errors: u32,
+}
+
+// SAFETY: `buffer` comes from a `&'static mut` owned by the driver alone
+unsafe impl<const P: usize> Send for Ws2812<P> {}
+
+impl<const P: usize> Ws2812<P> {
+    /// Drive `pin` from RMT channel `CH`.
+    ///
+    /// The RMT interrupt handler must already be set with
+    /// `Rmt::set_interrupt_handler`, and call [`Ws2812::on_interrupt`]. The
+    /// RMT peripheral must run from the 80 MHz APB clock.
+    pub fn new<const CH: u8>(
+        creator: ChannelCreator<'static, Blocking, CH>,
+        pin: impl PeripheralOutput<'static>,
+        buffer: &'static mut [PulseCode; P],
+    ) -> Result<Self, Error>
+    where
+        ChannelCreator<'static, Blocking, CH>: TxChannelCreator<'static, Blocking>,
+    {
+        let config = TxChannelConfig::default()
+            .with_clk_divider(1)
+            .with_idle_output_level(Level::Low)
+            .with_idle_output(true)
+            .with_carrier_modulation(false)
+            .with_memsize(MEMSIZE);
+        let channel = creator.configure_tx(pin, config)?;
+
+        let ticks = |ns: u32| (ns * Clocks::get().apb_clock.as_mhz() / 1000) as u16;
+        let pulse = |high_ns| {
+            PulseCode::new(
+                Level::High,
+                ticks(high_ns),
+                Level::Low,
+                ticks(PERIOD_NS - high_ns),
+            )
+        };
+
+        // Interrupt on refill, completion and errors
+        RMT::regs().int_ena().modify(|_, w| {
+            w.ch_tx_thr_event(CH).set_bit();
+            w.ch_tx_end(CH).set_bit();
+            w.ch_tx_err(CH).set_bit()
+        });
+
+        Ok(Self {
+            state: State::Idle(channel),
+            buffer: NonNull::from(buffer),
+            channel: CH,
+            zero: pulse(T0H_NS),
+            one: pulse(T1H_NS),
+            frames: 0,
+            errors: 0,
+        })
+    }
+
+    /// Whether a frame is still being sent
+    pub fn is_busy(&self) -> bool {
+        matches!(self.state, State::Sending(_))
+    }
+
+    /// Number of frames sent completely
+    pub fn frames(&self) -> u32 {
+        self.frames
+    }
+
+    /// Number of frames that failed to send
+    pub fn errors(&self) -> u32 {
+        self.errors
+    }
+
+    /// Start sending a frame of colors in wire order, such as
+    /// `Strip::wire()` (RGB) or `Strip::wire_rgbw()` (RGBW).
+    ///
+    /// Returns `WouldBlock` while the previous frame is still being sent. A
+    /// frame that fails to send is counted in [`Ws2812::errors`] and the next
+    /// write goes ahead; only [`Error::ChannelLost`] is final.
+    pub fn try_write<I, C>(&mut self, colors: I) -> nb::Result<(), Error>
+    where
+        I: IntoIterator<Item = C>,
+        C: AsRef<[u8]>,
+    {
+        let channel = match core::mem::replace(&mut self.state, State::Lost) {
+            State::Idle(channel) => channel,
+            State::Sending(transaction) => {
+                self.state = State::Sending(transaction);
+                return Err(nb::Error::WouldBlock);
+            }
+            State::Lost => return Err(nb::Error::Other(Error::ChannelLost)),
+        };
+
+        // SAFETY: no transmission is running, so nothing else uses the buffer
+        let buffer = unsafe { self.buffer.as_mut() };
+        let len = match self.encode(colors, buffer) {
+            Ok(len) => len,
+            Err(e) => {
+                self.state = State::Idle(channel);
+                return Err(nb::Error::Other(e));
+            }
+        };
+
+        // SAFETY: the buffer is `'static` and not written again until the
+        // transmission has finished and been dropped
+        let data = unsafe { &self.buffer.as_ref()[..len] };
+        // `transmit` only fails on an empty frame or a missing end marker,
+        // which `encode` rules out, but it consumes the channel when it does:
+        // report the error once, and `ChannelLost` from then on
+        match channel.transmit(data) {
+            Ok(transaction) => {
+                self.state = State::Sending(transaction);
+                Ok(())
+            }
+            Err(e) => {
+                self.errors = self.errors.wrapping_add(1);
+                Err(nb::Error::Other(e.into()))
+            }
+        }
+    }
+
+    /// Refill the channel RAM and finish the frame; call from the RMT
+    /// interrupt handler
+    pub fn on_interrupt(&mut self) {
+        match core::mem::replace(&mut self.state, State::Lost) {
+            State::Sending(mut transaction) => {
+                if !transaction.poll() {
+                    self.state = State::Sending(transaction);
+                    return;
+                }
+                self.state = match transaction.wait() {
+                    Ok(channel) => {
+                        self.frames = self.frames.wrapping_add(1);
+                        State::Idle(channel)
+                    }
+                    Err((_, channel)) => {
+                        self.errors = self.errors.wrapping_add(1);
+                        State::Idle(channel)
+                    }
+                };
+            }
+            state => {
+                // Nothing to do for this channel; clear it so the interrupt
+                // does not fire again
+                RMT::regs().int_clr().write(|w| {
+                    w.ch_tx_thr_event(self.channel).set_bit();
+                    w.ch_tx_end(self.channel).set_bit();
+                    w.ch_tx_err(self.channel).set_bit()
+                });
+                self.state = state;
+            }
+        }
+    }
+
+    /// Encode `colors` into `buffer`, returning the number of pulse codes
+    fn encode<I, C>(&self, colors: I, buffer: &mut [PulseCode; P]) -> Result<usize, Error>
+    where
+        I: IntoIterator<Item = C>,
+        C: AsRef<[u8]>,
+    {
+        let mut codes = buffer.iter_mut();
+        let mut len = 0;
+
+        for color in colors {
+            for &byte in color.as_ref() {
+                for bit in (0..8).rev() {
+                    let code = codes.next().ok_or(Error::BufferSizeExceeded)?;
+                    *code = if byte & (1 << bit) != 0 {
+                        self.one
+                    } else {
+                        self.zero
+                    };
+                    len += 1;
+                }
+            }
+        }
+
+        *codes.next().ok_or(Error::BufferSizeExceeded)? = PulseCode::end_marker();
+        Ok(len + 1)
+    }
+}